@@ -0,0 +1,34 @@
+// Gives the wasm build the browser's Gamepad API, the Rust side is in src/input/web_gamepad.rs
+miniquad_add_plugin({
+    name: "gamepad",
+    version: 1,
+    register_plugin: function (importObject) {
+        // Only pads with the standard mapping, their buttons and axes have fixed indices
+        function pad(slot) {
+            var pads = navigator.getGamepads ? navigator.getGamepads() : [];
+            var found = pads[slot];
+            return found && found.connected && found.mapping === "standard" ? found : null;
+        }
+
+        importObject.env.gamepad_connected = function () {
+            var count = navigator.getGamepads ? Math.min(navigator.getGamepads().length, 32) : 0;
+            var mask = 0;
+            for (var slot = 0; slot < count; slot++) {
+                if (pad(slot)) {
+                    mask |= 1 << slot;
+                }
+            }
+            return mask;
+        };
+
+        importObject.env.gamepad_button = function (slot, button) {
+            var found = pad(slot);
+            return found && found.buttons[button] && found.buttons[button].pressed ? 1 : 0;
+        };
+
+        importObject.env.gamepad_axis = function (slot, axis) {
+            var found = pad(slot);
+            return found && axis < found.axes.length ? found.axes[axis] : 0;
+        };
+    },
+});
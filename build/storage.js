@@ -0,0 +1,24 @@
+// Gives the wasm build access to localStorage, the Rust side is in src/storage.rs
+miniquad_add_plugin({
+    name: "storage",
+    version: 1,
+    register_plugin: function (importObject) {
+        function text(ptr, len) {
+            return new TextDecoder().decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+        }
+
+        importObject.env.storage_length = function (key, key_len) {
+            var value = localStorage.getItem(text(key, key_len));
+            return value === null ? -1 : new TextEncoder().encode(value).length;
+        };
+
+        importObject.env.storage_read = function (key, key_len, out) {
+            var bytes = new TextEncoder().encode(localStorage.getItem(text(key, key_len)) || "");
+            new Uint8Array(wasm_memory.buffer, out, bytes.length).set(bytes);
+        };
+
+        importObject.env.storage_write = function (key, key_len, value, value_len) {
+            localStorage.setItem(text(key, key_len), text(value, value_len));
+        };
+    },
+});
@@ -0,0 +1,3 @@
+mod screen;
+
+pub use screen::CharacterSelectScreen;
\ No newline at end of file
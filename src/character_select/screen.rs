@@ -0,0 +1,151 @@
+use macroquad::prelude::*;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use crate::state::GameState;
+use crate::input::InputMap;
+use crate::player::Character;
+use crate::stats::Stat;
+use crate::components::button::ButtonBuilder;
+use crate::components::text::TextComponent;
+use crate::components::table::Table;
+use crate::components::DrawableComponent;
+use crate::components::focus::FocusManager;
+use crate::components::layout::{Column, Row, Padding, ViewportWatcher, center_on_screen, is_mobile};
+
+// Stats worth comparing between characters, the rest is in the pause panel
+const CARD_STATS: [Stat; 4] = [Stat::MaxHealth, Stat::MoveSpeed, Stat::Might, Stat::Cooldown];
+
+pub struct CharacterSelectScreen<'a> {
+    layout: Column<'a>,
+    viewport: ViewportWatcher,
+    focus: FocusManager,
+    input: Rc<RefCell<InputMap>>,
+    characters: Rc<Vec<Character>>,
+    selected: Rc<Cell<usize>>,
+    state_transition: Rc<RefCell<Option<GameState>>>,
+}
+
+impl<'a> CharacterSelectScreen<'a> {
+    pub fn new(input: Rc<RefCell<InputMap>>, characters: Rc<Vec<Character>>) -> Self {
+        let state_transition = Rc::new(RefCell::new(None));
+        let selected = Rc::new(Cell::new(0));
+
+        Self {
+            layout: Self::build_layout(&characters, &selected, &state_transition),
+            viewport: ViewportWatcher::new(),
+            focus: FocusManager::new(),
+            input,
+            characters,
+            selected,
+            state_transition,
+        }
+    }
+
+    /// Character picked by the last click, the first one until then.
+    pub fn selected(&self) -> &Character {
+        &self.characters[self.selected.get()]
+    }
+
+    fn build_layout(
+        characters: &[Character],
+        selected: &Rc<Cell<usize>>,
+        state_transition: &Rc<RefCell<Option<GameState>>>,
+    ) -> Column<'a> {
+        let title_size = if is_mobile() { 50.0 } else { 60.0 };
+        let text_size = if is_mobile() { 36.0 } else { 22.0 };
+        let button_width = if is_mobile() { 500.0 } else { 200.0 };
+        let button_height = if is_mobile() { 100.0 } else { 60.0 };
+
+        let title = TextComponent::builder()
+            .text("Choose your character")
+            .font_size(title_size)
+            .color(WHITE)
+            .build();
+
+        let mut cards: Vec<Column<'a>> = Vec::new();
+
+        for (index, character) in characters.iter().enumerate() {
+            let state_play = Rc::clone(state_transition);
+            let selected = Rc::clone(selected);
+
+            let button = ButtonBuilder::new()
+                .size(button_width, button_height)
+                .label(character.name.clone())
+                .on_click(move || {
+                    selected.set(index);
+                    *state_play.borrow_mut() = Some(GameState::Playing);
+                })
+                .color(Color::from_rgba(90, 20, 20, 255))
+                .hover_color(Color::from_rgba(60, 20, 20, 255))
+                .build();
+
+            let description = TextComponent::builder()
+                .text(character.description.clone())
+                .font_size(text_size)
+                .color(LIGHTGRAY)
+                .build();
+
+            let mut rows = vec![("Weapon".to_string(), character.starting_weapon.clone())];
+            if let Some(passive) = &character.passive {
+                rows.push(("Passive".to_string(), passive.description.clone()));
+            }
+            for stat in CARD_STATS {
+                let value = character.base_stats
+                    .iter()
+                    .find(|(existing, _)| *existing == stat)
+                    .map_or(0.0, |(_, value)| *value);
+                rows.push((stat.label().to_string(), stat.format(value)));
+            }
+
+            cards.push(
+                Column::new()
+                    .centered()
+                    .spacing(12.0)
+                    .add_child(Box::new(button))
+                    .add_child(Box::new(description))
+                    .add_child(Box::new(Table::new(rows, text_size))),
+            );
+        }
+
+        // Cards sit side by side on desktop and stack up in portrait
+        let cards: Box<dyn DrawableComponent + 'a> = if is_mobile() {
+            Box::new(cards.into_iter().fold(Column::new().centered().spacing(40.0), |column, card| column.add_child(Box::new(card))))
+        } else {
+            Box::new(cards.into_iter().fold(Row::new().spacing(40.0), |row, card| row.add_child(Box::new(card))))
+        };
+
+        let state_back = Rc::clone(state_transition);
+        let back_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label("Back")
+            .on_click(move || {
+                *state_back.borrow_mut() = Some(GameState::Menu);
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        let mut layout = Column::new()
+            .centered()
+            .spacing(30.0)
+            .add_child(Box::new(Padding::new(Box::new(title)).vertical(20.0)))
+            .add_child(cards)
+            .add_child(Box::new(back_button));
+
+        center_on_screen(&mut layout);
+        layout
+    }
+
+    pub fn draw(&mut self) -> Option<GameState> {
+        if self.viewport.changed() {
+            self.layout = Self::build_layout(&self.characters, &self.selected, &self.state_transition);
+        }
+
+        self.focus.update(&mut self.layout, &self.input);
+
+        clear_background(Color::from_rgba(30, 30, 30, 255));
+        self.layout.draw();
+        self.state_transition.borrow_mut().take()
+    }
+}
@@ -0,0 +1,42 @@
+use macroquad::prelude::*;
+use crate::enemies::StatusEffect;
+
+/// Anything player weapons can hit, so every weapon works against every kind of target.
+///
+/// Targets are addressed by index so systems that store their entities in parallel
+/// vectors, like `EnemySystem`, can implement it without allocating per target.
+pub trait Damageable {
+    /// Indices of the live targets overlapping a circle in world space.
+    fn targets_in(&self, center: Vec2, radius: f32) -> Vec<usize>;
+
+    /// Applies a hit, returns the damage it actually dealt after armor and overkill.
+    fn apply_damage(&mut self, index: usize, amount: f32) -> f32;
+
+    /// Applies a status effect, targets that are immune simply ignore it.
+    fn apply_effect(&mut self, _index: usize, _effect: StatusEffect) {}
+}
+
+/// Identifies which `Damageable` a target index belongs to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TargetLayer {
+    Enemies,
+    Boss,
+    Props,
+}
+
+/// Outcome of a single landed hit, for anything that reacts to damage.
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub layer: TargetLayer,
+    pub index: usize,
+    pub position: Vec2,
+    /// Damage the target actually took.
+    pub amount: f32,
+    pub crit: bool,
+}
+
+/// True if an axis-aligned box overlaps a circle.
+pub fn rect_overlaps_circle(position: Vec2, size: Vec2, center: Vec2, radius: f32) -> bool {
+    let closest = center.clamp(position, position + size);
+    closest.distance_squared(center) <= radius * radius
+}
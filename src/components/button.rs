@@ -1,14 +1,18 @@
 use macroquad::prelude::*;
 use crate::components::DrawableComponent;
+use crate::components::focus::FocusCursor;
 use crate::components::layout::{ is_mobile };
 
 
 pub struct Button<'a> {
     rect: Rect,
-    label: &'a str,
+    label: String,
     on_click: Box<dyn FnMut() + 'a>,
     color: Color,
     hover_color: Color,
+    focused: bool,
+    mouse_armed: bool,
+    touch_id: Option<u64>,
 }
 
 impl<'a> Button<'a> {
@@ -16,7 +20,7 @@ impl<'a> Button<'a> {
         let mouse = mouse_position();
         let is_hovering = self.rect.contains(vec2(mouse.0, mouse.1));
 
-        let bg_color = if is_hovering {
+        let bg_color = if is_hovering || self.focused || self.touch_id.is_some() {
             self.hover_color
         } else {
             self.color
@@ -25,35 +29,95 @@ impl<'a> Button<'a> {
         draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, bg_color);
 
         let font_size = if is_mobile() { 50.0 } else { 30.0 };
-        let text_dim = measure_text(self.label, None, font_size as u16, 1.0);
+        let text_dim = measure_text(&self.label, None, font_size as u16, 1.0);
         draw_text(
-            self.label,
+            &self.label,
             self.rect.x + (self.rect.w - text_dim.width) / 2.0,
             self.rect.y + (self.rect.h + text_dim.height) / 2.0,
             font_size,
             WHITE,
         );
 
-        if is_hovering && is_mouse_button_pressed(MouseButton::Left) {
+        if self.focused {
+            let ring = 4.0;
+            draw_rectangle_lines(
+                self.rect.x - ring,
+                self.rect.y - ring,
+                self.rect.w + ring * 2.0,
+                self.rect.h + ring * 2.0,
+                ring,
+                WHITE,
+            );
+        }
+
+        if self.pointer_released(is_hovering) {
             (self.on_click)();
         }
     }
+
+    /// Clicks fire on release, and only if the press also started on the button.
+    fn pointer_released(&mut self, is_hovering: bool) -> bool {
+        let mut released = false;
+
+        if is_hovering && is_mouse_button_pressed(MouseButton::Left) {
+            self.mouse_armed = true;
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            released |= self.mouse_armed && is_hovering;
+            self.mouse_armed = false;
+        }
+
+        // Touches are handled explicitly since mouse emulation is disabled in main
+        for touch in touches() {
+            let inside = self.rect.contains(touch.position);
+
+            match touch.phase {
+                TouchPhase::Started if inside && self.touch_id.is_none() => {
+                    self.touch_id = Some(touch.id);
+                }
+                TouchPhase::Ended if Some(touch.id) == self.touch_id => {
+                    released |= inside;
+                    self.touch_id = None;
+                }
+                TouchPhase::Cancelled if Some(touch.id) == self.touch_id => {
+                    self.touch_id = None;
+                }
+                _ => {}
+            }
+        }
+
+        released
+    }
 }
 
 impl<'a> DrawableComponent for Button<'a> {
     fn draw(&mut self) {
         self.draw();
     }
+
+    fn measure(&self) -> Vec2 {
+        self.rect.size()
+    }
+
+    fn visit_focus(&mut self, cursor: &mut FocusCursor) {
+        self.focused = cursor.claim();
+
+        if self.focused && cursor.activate_requested() {
+            (self.on_click)();
+        }
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.rect.move_to(position);
+    }
 }
 
 // ------------------ Builder ------------------
 
 pub struct ButtonBuilder<'a> {
-    x: f32,
-    y: f32,
     width: f32,
     height: f32,
-    label: Option<&'a str>,
+    label: Option<String>,
     on_click: Box<dyn FnMut() + 'a>,
     color: Color,
     hover_color: Color,
@@ -62,8 +126,6 @@ pub struct ButtonBuilder<'a> {
 impl<'a> ButtonBuilder<'a> {
     pub fn new() -> Self {
         Self {
-            x: 0.0,
-            y: 0.0,
             width: 100.0,
             height: 40.0,
             label: None,
@@ -73,20 +135,14 @@ impl<'a> ButtonBuilder<'a> {
         }
     }
 
-    pub fn position(mut self, x: f32, y: f32) -> Self {
-        self.x = x;
-        self.y = y;
-        self
-    }
-
     pub fn size(mut self, width: f32, height: f32) -> Self {
         self.width = width;
         self.height = height;
         self
     }
 
-    pub fn label(mut self, label: &'a str) -> Self {
-        self.label = Some(label);
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
         self
     }
 
@@ -109,11 +165,15 @@ impl<'a> ButtonBuilder<'a> {
         let label = self.label.expect("Button label must be set");
 
         Button {
-            rect: Rect::new(self.x, self.y, self.width, self.height),
+            // Placed by the layout through `set_position`
+            rect: Rect::new(0.0, 0.0, self.width, self.height),
             label,
             on_click: self.on_click,
             color: self.color,
             hover_color: self.hover_color,
+            focused: false,
+            mouse_armed: false,
+            touch_id: None,
         }
     }
 }
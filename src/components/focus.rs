@@ -0,0 +1,114 @@
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use crate::components::DrawableComponent;
+use crate::input::{Action, InputMap};
+
+/// Walks the component tree handing out focus indexes in layout order.
+pub struct FocusCursor {
+    next: usize,
+    focused: Option<usize>,
+    activate: bool,
+}
+
+impl FocusCursor {
+    /// Claims the next focus index, returning true if it is the focused one.
+    pub fn claim(&mut self) -> bool {
+        let index = self.next;
+        self.next += 1;
+        self.focused == Some(index)
+    }
+
+    /// True when the focused component should trigger its action this frame.
+    pub fn activate_requested(&self) -> bool {
+        self.activate
+    }
+}
+
+/// Navigation pressed this frame, read out of the input map up front so button handlers
+/// are free to borrow it mutably.
+#[derive(Clone, Copy, Default)]
+pub struct FocusInput {
+    pub next: bool,
+    pub previous: bool,
+    pub activate: bool,
+}
+
+impl FocusInput {
+    pub fn read(input: &InputMap) -> Self {
+        let backwards = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+
+        // Movement bindings cover the arrow keys and the gamepad D-pad
+        let next = input.is_pressed(Action::MoveDown) || input.is_pressed(Action::MoveRight);
+        let previous = input.is_pressed(Action::MoveUp) || input.is_pressed(Action::MoveLeft);
+
+        Self {
+            next: next || (is_key_pressed(KeyCode::Tab) && !backwards),
+            previous: previous || (is_key_pressed(KeyCode::Tab) && backwards),
+            activate: input.is_pressed(Action::Confirm),
+        }
+    }
+}
+
+/// Moves focus between the focusable components of a layout with the keyboard
+/// or gamepad, activating the focused one on `Action::Confirm`.
+///
+/// Focus is dropped as soon as the mouse moves or the screen is touched, so the
+/// focus ring only shows up while the menu is being driven without a pointer.
+pub struct FocusManager {
+    focused: Option<usize>,
+    count: usize,
+    last_mouse: Option<Vec2>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        Self {
+            focused: None,
+            count: 0,
+            last_mouse: None,
+        }
+    }
+
+    pub fn update(&mut self, root: &mut dyn DrawableComponent, input: &RefCell<InputMap>) {
+        let mouse = Vec2::from(mouse_position());
+        if self.last_mouse.is_some_and(|last| last != mouse) || !touches().is_empty() {
+            self.focused = None;
+        }
+        self.last_mouse = Some(mouse);
+
+        let pressed = FocusInput::read(&input.borrow());
+        self.navigate(root, pressed);
+    }
+
+    /// Moves focus and visits the tree, running the focused component's action if asked to.
+    pub fn navigate(&mut self, root: &mut dyn DrawableComponent, pressed: FocusInput) {
+        if pressed.next {
+            self.move_focus(1);
+        }
+        if pressed.previous {
+            self.move_focus(-1);
+        }
+
+        let mut cursor = FocusCursor {
+            next: 0,
+            focused: self.focused,
+            activate: pressed.activate,
+        };
+
+        root.visit_focus(&mut cursor);
+        self.count = cursor.next;
+    }
+
+    fn move_focus(&mut self, step: isize) {
+        if self.count == 0 {
+            return;
+        }
+
+        let count = self.count as isize;
+        self.focused = Some(match self.focused {
+            Some(index) => (index as isize + step).rem_euclid(count) as usize,
+            None if step > 0 => 0,
+            None => self.count - 1,
+        });
+    }
+}
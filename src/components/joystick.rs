@@ -1,29 +1,119 @@
 use macroquad::prelude::*;
 use crate::components::layout::is_mobile;
+use crate::input::remove_deadzone;
+
+// A touch shorter than this that barely moves the knob counts as a tap
+const TAP_DURATION: f64 = 0.25;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum JoystickMode {
+    /// The base stays at its rest position, touches must start inside it.
+    Fixed,
+    /// The base jumps to wherever the thumb first touches its half of the screen.
+    Floating,
+}
+
+/// Half of the screen a joystick belongs to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ScreenSide {
+    Left,
+    Right,
+}
+
+impl ScreenSide {
+    pub fn contains(&self, position: Vec2) -> bool {
+        match self {
+            ScreenSide::Left => position.x < screen_width() / 2.0,
+            ScreenSide::Right => position.x >= screen_width() / 2.0,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Joystick {
     pub base_pos: Vec2,
+    pub rest_pos: Vec2,
     pub radius: f32,
     pub knob_radius: f32,
     pub dragging: bool,
     pub knob_pos: Vec2,
     pub touch_id: Option<u64>,
+    pub mode: JoystickMode,
+    pub side: ScreenSide,
+    /// Fraction of the radius ignored around the center.
+    pub deadzone: f32,
+    touch_started_at: f64,
+    max_travel: f32,
+    tapped: bool,
 }
 
 impl Joystick {
     pub fn new(base_pos: Vec2, radius: f32) -> Self {
         Self {
             base_pos,
+            rest_pos: base_pos,
             radius,
             knob_radius: radius * 0.4,
             dragging: false,
             knob_pos: base_pos,
             touch_id: None,
+            mode: JoystickMode::Fixed,
+            side: ScreenSide::Left,
+            deadzone: 0.1,
+            touch_started_at: 0.0,
+            max_travel: 0.0,
+            tapped: false,
+        }
+    }
+
+    pub fn mode(mut self, mode: JoystickMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn side(mut self, side: ScreenSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// Bottom of the screen, centered on the given half.
+    pub fn default_base_position(radius: f32, side: ScreenSide) -> Vec2 {
+        let x = match side {
+            ScreenSide::Left => screen_width() * 0.25,
+            ScreenSide::Right => screen_width() * 0.75,
+        };
+
+        vec2(x, screen_height() - radius - 20.0)
+    }
+
+    /// Moves the joystick rest position, releasing any drag in progress.
+    pub fn set_base_position(&mut self, base_pos: Vec2) {
+        self.rest_pos = base_pos;
+        self.release();
+    }
+
+    fn release(&mut self) {
+        self.dragging = false;
+        self.touch_id = None;
+        self.base_pos = self.rest_pos;
+        self.knob_pos = self.rest_pos;
+    }
+
+    fn accepts(&self, touch_pos: Vec2) -> bool {
+        match self.mode {
+            JoystickMode::Fixed => touch_pos.distance(self.base_pos) < self.radius,
+            JoystickMode::Floating => self.side.contains(touch_pos),
         }
     }
 
     pub fn update(&mut self) {
+        self.tapped = false;
+
         if !is_mobile() {
             return;
         }
@@ -34,13 +124,16 @@ impl Joystick {
         for touch in &all_touches {
             let touch_pos = vec2(touch.position.x, touch.position.y);
 
-            if self.touch_id.is_none() && touch.phase == TouchPhase::Started {
-                // Start dragging if touch began inside the joystick base
-                if touch_pos.distance(self.base_pos) < self.radius {
-                    self.dragging = true;
-                    self.touch_id = Some(touch.id);
-                    self.knob_pos = touch_pos;
+            if self.touch_id.is_none() && touch.phase == TouchPhase::Started && self.accepts(touch_pos) {
+                if self.mode == JoystickMode::Floating {
+                    self.base_pos = touch_pos;
                 }
+
+                self.dragging = true;
+                self.touch_id = Some(touch.id);
+                self.knob_pos = touch_pos;
+                self.touch_started_at = get_time();
+                self.max_travel = 0.0;
             }
 
             if Some(touch.id) == self.touch_id {
@@ -48,11 +141,13 @@ impl Joystick {
                     TouchPhase::Moved | TouchPhase::Stationary => {
                         let direction = (touch_pos - self.base_pos).clamp_length_max(self.radius);
                         self.knob_pos = self.base_pos + direction;
+                        self.max_travel = self.max_travel.max(direction.length());
                     }
                     TouchPhase::Ended | TouchPhase::Cancelled => {
-                        self.dragging = false;
-                        self.touch_id = None;
-                        self.knob_pos = self.base_pos;
+                        self.tapped = touch.phase == TouchPhase::Ended
+                            && self.max_travel < self.deadzone * self.radius
+                            && get_time() - self.touch_started_at < TAP_DURATION;
+                        self.release();
                     }
                     _ => {}
                 }
@@ -61,29 +156,31 @@ impl Joystick {
 
         // Reset knob if no valid touches
         if self.touch_id.is_some() && !all_touches.iter().any(|t| t.id == self.touch_id.unwrap()) {
-            self.dragging = false;
-            self.touch_id = None;
-            self.knob_pos = self.base_pos;
+            self.release();
         }
     }
 
     pub fn draw(&self) {
         if is_mobile() {
-            draw_circle(self.base_pos.x, self.base_pos.y, self.radius, Color::from_rgba(0, 0, 0, 100));
-            draw_circle(self.knob_pos.x, self.knob_pos.y, self.knob_radius, Color::from_rgba(255, 255, 255, 180));
+            // An idle floating stick only hints where it rests
+            let alpha = if self.mode == JoystickMode::Floating && !self.dragging { 0.4 } else { 1.0 };
+
+            draw_circle(self.base_pos.x, self.base_pos.y, self.radius, Color::from_rgba(0, 0, 0, (100.0 * alpha) as u8));
+            draw_circle(self.knob_pos.x, self.knob_pos.y, self.knob_radius, Color::from_rgba(255, 255, 255, (180.0 * alpha) as u8));
         }
     }
 
-    pub fn direction(&self) -> Vec2 {
-        if self.dragging {
-            let dir = self.knob_pos - self.base_pos;
-            if dir.length() > 5.0 {
-                dir.normalize()
-            } else {
-                vec2(0.0, 0.0)
-            }
-        } else {
-            vec2(0.0, 0.0)
+    /// Analog value with the deadzone removed, its length goes from 0 to 1.
+    pub fn value(&self) -> Vec2 {
+        if !self.dragging {
+            return Vec2::ZERO;
         }
+
+        remove_deadzone((self.knob_pos - self.base_pos) / self.radius, self.deadzone)
+    }
+
+    /// True on the frame a short touch without any drag was released.
+    pub fn was_tapped(&self) -> bool {
+        self.tapped
     }
 }
@@ -1,6 +1,10 @@
 use macroquad::prelude::*;
 use crate::components::DrawableComponent;
+use crate::components::focus::FocusCursor;
 
+// ------------------ Column ------------------
+
+/// Stacks its children vertically, separated by `spacing`.
 pub struct Column<'a> {
     children: Vec<Box<dyn DrawableComponent + 'a>>,
     center: bool,
@@ -16,6 +20,86 @@ impl<'a> Column<'a> {
         }
     }
 
+    /// Centers every child horizontally inside the column width.
+    pub fn centered(mut self) -> Self {
+        self.center = true;
+        self
+    }
+
+    pub fn spacing(mut self, value: f32) -> Self {
+        self.spacing = value;
+        self
+    }
+
+    pub fn add_child(mut self, child: Box<dyn DrawableComponent + 'a>) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl<'a> DrawableComponent for Column<'a> {
+    fn draw(&mut self) {
+        for child in self.children.iter_mut() {
+            child.draw();
+        }
+    }
+
+    fn visit_focus(&mut self, cursor: &mut FocusCursor) {
+        for child in self.children.iter_mut() {
+            child.visit_focus(cursor);
+        }
+    }
+
+    fn measure(&self) -> Vec2 {
+        let mut size = Vec2::ZERO;
+
+        for child in &self.children {
+            let child_size = child.measure();
+            size.x = size.x.max(child_size.x);
+            size.y += child_size.y;
+        }
+
+        size.y += self.spacing * self.children.len().saturating_sub(1) as f32;
+        size
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        let width = self.measure().x;
+        let mut y = position.y;
+
+        for child in self.children.iter_mut() {
+            let child_size = child.measure();
+            let x = if self.center {
+                position.x + (width - child_size.x) / 2.0
+            } else {
+                position.x
+            };
+
+            child.set_position(vec2(x, y));
+            y += child_size.y + self.spacing;
+        }
+    }
+}
+
+// ------------------ Row ------------------
+
+/// Places its children side by side, separated by `spacing`.
+pub struct Row<'a> {
+    children: Vec<Box<dyn DrawableComponent + 'a>>,
+    center: bool,
+    spacing: f32,
+}
+
+impl<'a> Row<'a> {
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            center: false,
+            spacing: 10.0,
+        }
+    }
+
+    /// Centers every child vertically inside the row height.
     pub fn centered(mut self) -> Self {
         self.center = true;
         self
@@ -30,17 +114,222 @@ impl<'a> Column<'a> {
         self.children.push(child);
         self
     }
+}
+
+impl<'a> DrawableComponent for Row<'a> {
+    fn draw(&mut self) {
+        for child in self.children.iter_mut() {
+            child.draw();
+        }
+    }
+
+    fn visit_focus(&mut self, cursor: &mut FocusCursor) {
+        for child in self.children.iter_mut() {
+            child.visit_focus(cursor);
+        }
+    }
+
+    fn measure(&self) -> Vec2 {
+        let mut size = Vec2::ZERO;
+
+        for child in &self.children {
+            let child_size = child.measure();
+            size.x += child_size.x;
+            size.y = size.y.max(child_size.y);
+        }
+
+        size.x += self.spacing * self.children.len().saturating_sub(1) as f32;
+        size
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        let height = self.measure().y;
+        let mut x = position.x;
+
+        for child in self.children.iter_mut() {
+            let child_size = child.measure();
+            let y = if self.center {
+                position.y + (height - child_size.y) / 2.0
+            } else {
+                position.y
+            };
+
+            child.set_position(vec2(x, y));
+            x += child_size.x + self.spacing;
+        }
+    }
+}
+
+// ------------------ Stack ------------------
+
+/// Draws its children on top of each other, in insertion order.
+pub struct Stack<'a> {
+    children: Vec<Box<dyn DrawableComponent + 'a>>,
+}
+
+impl<'a> Stack<'a> {
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+        }
+    }
+
+    pub fn add_child(mut self, child: Box<dyn DrawableComponent + 'a>) -> Self {
+        self.children.push(child);
+        self
+    }
+}
 
-    pub fn draw(&mut self) {
+impl<'a> DrawableComponent for Stack<'a> {
+    fn draw(&mut self) {
         for child in self.children.iter_mut() {
             child.draw();
         }
     }
+
+    fn visit_focus(&mut self, cursor: &mut FocusCursor) {
+        for child in self.children.iter_mut() {
+            child.visit_focus(cursor);
+        }
+    }
+
+    fn measure(&self) -> Vec2 {
+        self.children
+            .iter()
+            .map(|child| child.measure())
+            .fold(Vec2::ZERO, Vec2::max)
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        for child in self.children.iter_mut() {
+            child.set_position(position);
+        }
+    }
+}
+
+// ------------------ Spacer ------------------
+
+/// Empty component that only takes up space, useful to give cells a fixed size.
+pub struct Spacer {
+    size: Vec2,
+}
+
+impl Spacer {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            size: vec2(width, height),
+        }
+    }
+}
+
+impl DrawableComponent for Spacer {
+    fn draw(&mut self) {}
+
+    fn measure(&self) -> Vec2 {
+        self.size
+    }
+
+    fn set_position(&mut self, _position: Vec2) {}
+}
+
+// ------------------ Padding ------------------
+
+/// Adds empty space above and below a single child.
+pub struct Padding<'a> {
+    child: Box<dyn DrawableComponent + 'a>,
+    top: f32,
+    bottom: f32,
+}
+
+impl<'a> Padding<'a> {
+    pub fn new(child: Box<dyn DrawableComponent + 'a>) -> Self {
+        Self {
+            child,
+            top: 0.0,
+            bottom: 0.0,
+        }
+    }
+
+    pub fn vertical(mut self, value: f32) -> Self {
+        self.top = value;
+        self.bottom = value;
+        self
+    }
+}
+
+impl<'a> DrawableComponent for Padding<'a> {
+    fn draw(&mut self) {
+        self.child.draw();
+    }
+
+    fn visit_focus(&mut self, cursor: &mut FocusCursor) {
+        self.child.visit_focus(cursor);
+    }
+
+    fn measure(&self) -> Vec2 {
+        self.child.measure() + vec2(0.0, self.top + self.bottom)
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.child.set_position(position + vec2(0.0, self.top));
+    }
+}
+
+/// Snapshot of the screen properties the UI layout depends on.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub width: f32,
+    pub height: f32,
+    pub mobile: bool,
+}
+
+impl Viewport {
+    pub fn current() -> Self {
+        Self {
+            width: screen_width(),
+            height: screen_height(),
+            mobile: is_mobile(),
+        }
+    }
+}
+
+/// Detects window resizes and orientation changes so layouts can be rebuilt.
+///
+/// Component sizes depend on `is_mobile()`, so screens rebuild their whole tree on a change
+/// instead of only repositioning it.
+pub struct ViewportWatcher {
+    last: Viewport,
+}
+
+impl ViewportWatcher {
+    pub fn new() -> Self {
+        Self {
+            last: Viewport::current(),
+        }
+    }
+
+    /// Returns true once for every change of the viewport since the previous call.
+    pub fn changed(&mut self) -> bool {
+        let current = Viewport::current();
+
+        if current != self.last {
+            self.last = current;
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Places `component` so that it is centered on the screen.
+pub fn center_on_screen(component: &mut dyn DrawableComponent) {
+    let screen_center = vec2(screen_width() / 2.0, screen_height() / 2.0);
+    component.set_position(screen_center - component.measure() / 2.0);
 }
 
 pub fn is_mobile() -> bool {
     let width = screen_width();
     let height = screen_height();
     let aspect_ratio = height / width;
-    return aspect_ratio > 1.3;
-}
\ No newline at end of file
+    aspect_ratio > 1.3
+}
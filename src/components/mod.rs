@@ -2,7 +2,21 @@ pub mod button;
 pub mod text;
 pub mod layout;
 pub mod joystick;
+pub mod focus;
+pub mod table;
+
+use macroquad::prelude::*;
+use focus::FocusCursor;
 
 pub trait DrawableComponent {
     fn draw(&mut self);
-}
\ No newline at end of file
+
+    /// Size the component wants to occupy, used by containers to lay out their children.
+    fn measure(&self) -> Vec2;
+
+    /// Moves the top-left corner of the component to `position`.
+    fn set_position(&mut self, position: Vec2);
+
+    /// Containers forward the cursor to their children, focusable components claim an index.
+    fn visit_focus(&mut self, _cursor: &mut FocusCursor) {}
+}
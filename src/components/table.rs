@@ -0,0 +1,64 @@
+use macroquad::prelude::*;
+use crate::components::DrawableComponent;
+
+/// Two column list of labels and values, values are right aligned.
+pub struct Table {
+    rows: Vec<(String, String)>,
+    font_size: f32,
+    row_spacing: f32,
+    column_spacing: f32,
+    color: Color,
+    position: Vec2,
+}
+
+impl Table {
+    pub fn new(rows: Vec<(String, String)>, font_size: f32) -> Self {
+        Self {
+            rows,
+            font_size,
+            row_spacing: font_size * 0.3,
+            column_spacing: font_size,
+            color: WHITE,
+            position: Vec2::ZERO,
+        }
+    }
+
+    fn column_widths(&self) -> (f32, f32) {
+        self.rows.iter().fold((0.0_f32, 0.0_f32), |(label_width, value_width), (label, value)| {
+            (
+                label_width.max(measure_text(label, None, self.font_size as u16, 1.0).width),
+                value_width.max(measure_text(value, None, self.font_size as u16, 1.0).width),
+            )
+        })
+    }
+}
+
+impl DrawableComponent for Table {
+    fn draw(&mut self) {
+        let (label_width, value_width) = self.column_widths();
+        let right = self.position.x + label_width + self.column_spacing + value_width;
+        let mut y = self.position.y + self.font_size;
+
+        for (label, value) in &self.rows {
+            let value_dim = measure_text(value, None, self.font_size as u16, 1.0);
+
+            draw_text(label, self.position.x, y, self.font_size, GRAY);
+            draw_text(value, right - value_dim.width, y, self.font_size, self.color);
+            y += self.font_size + self.row_spacing;
+        }
+    }
+
+    fn measure(&self) -> Vec2 {
+        let (label_width, value_width) = self.column_widths();
+        let rows = self.rows.len() as f32;
+
+        vec2(
+            label_width + self.column_spacing + value_width,
+            rows * self.font_size + (rows - 1.0).max(0.0) * self.row_spacing,
+        )
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+}
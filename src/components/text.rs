@@ -1,11 +1,11 @@
 use macroquad::prelude::*;
+use std::borrow::Cow;
 use crate::components::DrawableComponent;
 
 pub struct TextComponent<'a> {
-    text: &'a str,
+    text: Cow<'a, str>,
     font_size: f32,
     color: Color,
-    center: bool,
     x: f32,
     y: f32,
 }
@@ -16,41 +16,40 @@ impl<'a> TextComponent<'a> {
             text: None,
             font_size: None,
             color: WHITE,
-            center: true,
-            x: 0.0,
-            y: 0.0,
         }
     }
 }
 
 impl<'a> DrawableComponent for TextComponent<'a> {
     fn draw(&mut self) {
-        let measured = measure_text(self.text, None, self.font_size as u16, 1.0);
+        let measured = measure_text(&self.text, None, self.font_size as u16, 1.0);
+
+        // (x, y) is the top-left corner, draw_text expects the baseline
+        draw_text(&self.text, self.x, self.y + measured.offset_y, self.font_size, self.color);
+    }
 
-        let x = if self.center {
-            self.x - measured.width / 2.0
-        } else {
-            self.x
-        };
+    fn measure(&self) -> Vec2 {
+        let measured = measure_text(&self.text, None, self.font_size as u16, 1.0);
+        vec2(measured.width, measured.height)
+    }
 
-        draw_text(self.text, x, self.y, self.font_size, self.color);
+    fn set_position(&mut self, position: Vec2) {
+        self.x = position.x;
+        self.y = position.y;
     }
 }
 
 // ------------------ Builder ------------------
 
 pub struct TextComponentBuilder<'a> {
-    text: Option<&'a str>,
+    text: Option<Cow<'a, str>>,
     font_size: Option<f32>,
     color: Color,
-    center: bool,
-    x: f32,
-    y: f32,
 }
 
 impl<'a> TextComponentBuilder<'a> {
-    pub fn text(mut self, text: &'a str) -> Self {
-        self.text = Some(text);
+    pub fn text(mut self, text: impl Into<Cow<'a, str>>) -> Self {
+        self.text = Some(text.into());
         self
     }
 
@@ -64,18 +63,6 @@ impl<'a> TextComponentBuilder<'a> {
         self
     }
 
-    #[allow(dead_code)]
-    pub fn at(mut self, x: f32, y: f32) -> Self {
-        self.x = x;
-        self.y = y;
-        self
-    }
-
-    pub fn align_center(mut self, center: bool) -> Self {
-        self.center = center;
-        self
-    }
-
     pub fn build(self) -> TextComponent<'a> {
         let text = self.text.expect("TextComponent text must be set");
         let font_size = self.font_size.expect("TextComponent font_size must be set");
@@ -84,9 +71,9 @@ impl<'a> TextComponentBuilder<'a> {
             text,
             font_size,
             color: self.color,
-            center: self.center,
-            x: self.x,
-            y: self.y,
+            // Placed by the layout through `set_position`
+            x: 0.0,
+            y: 0.0,
         }
     }
-}
\ No newline at end of file
+}
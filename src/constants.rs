@@ -1,9 +1,13 @@
 use macroquad::prelude::*;
 use crate::components::layout::is_mobile;
 
+/// Size of the world when no map could be loaded, otherwise the map decides.
 pub const WORLD_WIDTH: f32 = 4000.0;
 pub const WORLD_HEIGHT: f32 = 4000.0;
 
+/// Most particles alive at once, bursts thin out as this fills up.
+pub const PARTICLE_BUDGET: usize = 2000;
+
 pub fn virtual_height() -> f32 {
     if is_mobile() {
         screen_height() * 0.5
@@ -0,0 +1,147 @@
+use macroquad::prelude::*;
+
+use crate::combat::{Hit, TargetLayer};
+
+const LIFETIME: f32 = 0.8;
+/// World units per second the numbers float up.
+const RISE_SPEED: f32 = 60.0;
+const FONT_SIZE: f32 = 26.0;
+const CRIT_COLOR: Color = Color::new(1.0, 0.8, 0.1, 1.0);
+const HEAL_COLOR: Color = Color::new(0.35, 1.0, 0.45, 1.0);
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumberKind {
+    Damage { crit: bool },
+    Heal,
+}
+
+#[derive(Clone, Copy)]
+struct FloatingNumber {
+    /// World-space point the number rises from.
+    position: Vec2,
+    amount: f32,
+    kind: NumberKind,
+    /// Hit target, so hits on the same target in one frame add up into one number.
+    target: Option<(TargetLayer, usize)>,
+    age: f32,
+}
+
+/// Fixed pool of numbers floating over damaged enemies and the healed player.
+///
+/// Positions are in world space, drawing projects them through the world camera so the
+/// text stays upright whichever way the camera's y axis points.
+pub struct DamageNumbers {
+    numbers: Vec<FloatingNumber>,
+    alive: usize,
+    /// Numbers from `spawned_from` on were added this frame and can still be merged into.
+    spawned_from: usize,
+}
+
+impl DamageNumbers {
+    pub fn new(capacity: usize) -> Self {
+        let dead = FloatingNumber {
+            position: Vec2::ZERO,
+            amount: 0.0,
+            kind: NumberKind::Heal,
+            target: None,
+            age: 0.0,
+        };
+
+        Self {
+            numbers: vec![dead; capacity],
+            alive: 0,
+            spawned_from: 0,
+        }
+    }
+
+    /// Shows the damage of a hit at `position`, adding to the number already shown for its
+    /// target this frame.
+    pub fn hit(&mut self, hit: &Hit, position: Vec2) {
+        if hit.amount <= 0.0 {
+            return;
+        }
+
+        let target = Some((hit.layer, hit.index));
+        let merged = self.numbers[self.spawned_from..self.alive]
+            .iter_mut()
+            .find(|number| number.target == target);
+
+        if let Some(number) = merged {
+            number.amount += hit.amount;
+            if hit.crit {
+                number.kind = NumberKind::Damage { crit: true };
+            }
+            return;
+        }
+
+        self.spawn(FloatingNumber {
+            position,
+            amount: hit.amount,
+            kind: NumberKind::Damage { crit: hit.crit },
+            target,
+            age: 0.0,
+        });
+    }
+
+    pub fn heal(&mut self, position: Vec2, amount: f32) {
+        self.spawn(FloatingNumber {
+            position,
+            amount,
+            kind: NumberKind::Heal,
+            target: None,
+            age: 0.0,
+        });
+    }
+
+    /// Numbers that don't fit in the pool are dropped.
+    fn spawn(&mut self, number: FloatingNumber) {
+        if self.alive == self.numbers.len() {
+            return;
+        }
+
+        // A little sideways jitter keeps rapid hits from stacking on one spot
+        let jitter = vec2(rand::gen_range(-12.0, 12.0), 0.0);
+        self.numbers[self.alive] = FloatingNumber { position: number.position + jitter, ..number };
+        self.alive += 1;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let mut i = 0;
+        while i < self.alive {
+            let number = &mut self.numbers[i];
+            number.age += dt;
+
+            if number.age >= LIFETIME {
+                self.alive -= 1;
+                self.numbers.swap(i, self.alive);
+                continue;
+            }
+
+            number.position.y += RISE_SPEED * dt;
+            i += 1;
+        }
+
+        self.spawned_from = self.alive;
+    }
+
+    /// Draws in screen space, call after switching back to the default camera.
+    pub fn draw(&self, camera: &Camera2D) {
+        for number in &self.numbers[..self.alive] {
+            let t = number.age / LIFETIME;
+            let (mut color, font_size, text) = match number.kind {
+                NumberKind::Damage { crit: true } => (CRIT_COLOR, FONT_SIZE * 1.4, format!("{:.0}!", number.amount)),
+                NumberKind::Damage { crit: false } => (WHITE, FONT_SIZE, format!("{:.0}", number.amount)),
+                NumberKind::Heal => (HEAL_COLOR, FONT_SIZE, format!("+{:.0}", number.amount)),
+            };
+            // Fully opaque for the first half, then fading out
+            color.a = (2.0 - t * 2.0).min(1.0);
+
+            let screen = camera.world_to_screen(number.position);
+            let size = measure_text(&text, None, font_size as u16, 1.0);
+            let x = screen.x - size.width / 2.0;
+
+            draw_text(&text, x + 2.0, screen.y + 2.0, font_size, Color::new(0.0, 0.0, 0.0, color.a * 0.6));
+            draw_text(&text, x, screen.y, font_size, color);
+        }
+    }
+}
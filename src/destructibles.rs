@@ -0,0 +1,236 @@
+use macroquad::prelude::*;
+
+use crate::combat::{Damageable, rect_overlaps_circle};
+use crate::enemies::PositionOverlap;
+use crate::pickups::{PickupKind, PowerUp};
+use crate::stats::{ModifierKind, Stat};
+
+/// What a broken prop can leave behind, with relative weights.
+const DROP_TABLE: [(f32, PickupKind); 6] = [
+    (4.0, PickupKind::Heal(20.0)),
+    (5.0, PickupKind::Gold(5)),
+    (1.0, PickupKind::Gold(25)),
+    (1.0, PickupKind::PowerUp(PowerUp { stat: Stat::Might, kind: ModifierKind::PercentAdditive, value: 0.5, duration: 10.0 })),
+    (1.0, PickupKind::PowerUp(PowerUp { stat: Stat::MoveSpeed, kind: ModifierKind::PercentAdditive, value: 0.3, duration: 10.0 })),
+    (1.0, PickupKind::PowerUp(PowerUp { stat: Stat::PickupRadius, kind: ModifierKind::PercentMultiplicative, value: 3.0, duration: 8.0 })),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DestructibleKind {
+    Barrel,
+    Crate,
+    /// Sturdier and always drops something.
+    Brazier,
+}
+
+impl DestructibleKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Barrel" => Some(Self::Barrel),
+            "Crate" => Some(Self::Crate),
+            "Brazier" => Some(Self::Brazier),
+            _ => None,
+        }
+    }
+
+    fn size(&self) -> Vec2 {
+        match self {
+            Self::Barrel => vec2(44.0, 56.0),
+            Self::Crate => vec2(52.0, 52.0),
+            Self::Brazier => vec2(48.0, 72.0),
+        }
+    }
+
+    fn health(&self) -> f32 {
+        match self {
+            Self::Barrel | Self::Crate => 15.0,
+            Self::Brazier => 40.0,
+        }
+    }
+
+    fn drop_chance(&self) -> f32 {
+        match self {
+            Self::Barrel | Self::Crate => 0.6,
+            Self::Brazier => 1.0,
+        }
+    }
+
+    /// Rolls the drop table, `None` when the prop leaves nothing.
+    pub fn roll_drop(&self, luck: f32) -> Option<PickupKind> {
+        if rand::gen_range(0.0, 1.0) >= self.drop_chance() * luck {
+            return None;
+        }
+
+        let total: f32 = DROP_TABLE.iter().map(|(weight, _)| weight).sum();
+        Some(pick_drop(rand::gen_range(0.0, total)))
+    }
+}
+
+/// The drop table entry `roll` lands on, counting up through the weights.
+fn pick_drop(mut roll: f32) -> PickupKind {
+    for (weight, kind) in DROP_TABLE {
+        if roll < weight {
+            return kind;
+        }
+        roll -= weight;
+    }
+
+    // Only reachable through rounding at the very top of the range
+    DROP_TABLE[DROP_TABLE.len() - 1].1
+}
+
+/// A breakable prop, `position` is the corner it stands on like obstacles.
+pub struct Destructible {
+    pub kind: DestructibleKind,
+    pub position: Vec2,
+    pub size: Vec2,
+    health: f32,
+}
+
+impl Destructible {
+    fn is_broken(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    fn draw(&self) {
+        let Vec2 { x, y } = self.position;
+        let Vec2 { x: w, y: h } = self.size;
+
+        draw_ellipse(x + w / 2.0, y + 3.0, w * 0.5, 7.0, 0.0, Color::new(0.0, 0.0, 0.0, 0.3));
+
+        match self.kind {
+            DestructibleKind::Barrel => {
+                draw_rectangle(x, y, w, h, Color::from_rgba(125, 80, 45, 255));
+                draw_rectangle(x, y + h * 0.2, w, 5.0, Color::from_rgba(70, 70, 75, 255));
+                draw_rectangle(x, y + h * 0.75, w, 5.0, Color::from_rgba(70, 70, 75, 255));
+            }
+            DestructibleKind::Crate => {
+                draw_rectangle(x, y, w, h, Color::from_rgba(160, 120, 70, 255));
+                draw_rectangle_lines(x, y, w, h, 4.0, Color::from_rgba(100, 70, 40, 255));
+                draw_line(x, y, x + w, y + h, 4.0, Color::from_rgba(100, 70, 40, 255));
+            }
+            DestructibleKind::Brazier => {
+                draw_rectangle(x + w * 0.35, y, w * 0.3, h * 0.6, Color::from_rgba(60, 60, 65, 255));
+                draw_rectangle(x, y + h * 0.55, w, h * 0.15, Color::from_rgba(80, 80, 90, 255));
+
+                let flicker = (get_time() * 12.0 + x as f64).sin() as f32 * 3.0;
+                draw_circle(x + w / 2.0, y + h * 0.8, w * 0.3 + flicker, Color::from_rgba(255, 140, 40, 220));
+                draw_circle(x + w / 2.0, y + h * 0.78, w * 0.15, Color::from_rgba(255, 230, 120, 255));
+            }
+        }
+    }
+}
+
+/// Breakable props in the world, hit through `Damageable` like enemies.
+///
+/// Broken props stay in place until `retain_in`, which shifts the indices of the rest, so
+/// anything holding prop indices has to let go of them after it.
+pub struct Destructibles {
+    items: Vec<Destructible>,
+    /// Props broken since the last `take_broken`, with their center.
+    broken: Vec<(Vec2, DestructibleKind)>,
+}
+
+impl Destructibles {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            broken: Vec::new(),
+        }
+    }
+
+    pub fn spawn(&mut self, kind: DestructibleKind, position: Vec2) {
+        self.items.push(Destructible {
+            kind,
+            position,
+            size: kind.size(),
+            health: kind.health(),
+        });
+    }
+
+    /// Drops props outside `area` and the broken ones, for worlds that stream in chunks.
+    pub fn retain_in(&mut self, area: Rect) {
+        self.items.retain(|item| !item.is_broken() && area.contains(item.position));
+    }
+
+    /// Props broken since the last call, so the caller can drop their loot.
+    pub fn take_broken(&mut self) -> Vec<(Vec2, DestructibleKind)> {
+        std::mem::take(&mut self.broken)
+    }
+
+    /// Draws the props on one side of the player, farthest first.
+    pub fn draw(&self, target_pos: Vec2, overlap: PositionOverlap) {
+        let mut visible: Vec<&Destructible> = self.items.iter()
+            .filter(|item| !item.is_broken())
+            .filter(|item| match overlap {
+                PositionOverlap::Behind => item.position.y > target_pos.y,
+                PositionOverlap::InFront => item.position.y <= target_pos.y,
+            })
+            .collect();
+
+        visible.sort_by(|a, b| b.position.y.total_cmp(&a.position.y));
+
+        for item in visible {
+            item.draw();
+        }
+    }
+}
+
+impl Damageable for Destructibles {
+    fn targets_in(&self, center: Vec2, radius: f32) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&i| !self.items[i].is_broken())
+            .filter(|&i| rect_overlaps_circle(self.items[i].position, self.items[i].size, center, radius))
+            .collect()
+    }
+
+    fn apply_damage(&mut self, index: usize, amount: f32) -> f32 {
+        let item = &mut self.items[index];
+        if item.is_broken() {
+            return 0.0;
+        }
+
+        let damage = amount.min(item.health);
+        item.health -= damage;
+        if item.is_broken() {
+            self.broken.push((item.position + item.size / 2.0, item.kind));
+        }
+
+        damage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_land_on_weighted_entries() {
+        assert!(pick_drop(0.0) == PickupKind::Heal(20.0));
+        assert!(pick_drop(3.9) == PickupKind::Heal(20.0));
+        assert!(pick_drop(4.0) == PickupKind::Gold(5));
+        assert!(pick_drop(9.5) == PickupKind::Gold(25));
+        assert!(matches!(pick_drop(10.5), PickupKind::PowerUp(PowerUp { stat: Stat::Might, .. })));
+        assert!(matches!(pick_drop(12.99), PickupKind::PowerUp(PowerUp { stat: Stat::PickupRadius, .. })));
+        assert!(matches!(pick_drop(13.0), PickupKind::PowerUp(PowerUp { stat: Stat::PickupRadius, .. })));
+    }
+
+    #[test]
+    fn braziers_always_drop_and_no_luck_drops_nothing() {
+        rand::srand(3);
+        for _ in 0..200 {
+            assert!(DestructibleKind::Brazier.roll_drop(1.0).is_some());
+            assert!(DestructibleKind::Crate.roll_drop(0.0).is_none());
+        }
+    }
+
+    #[test]
+    fn treasure_never_comes_from_props() {
+        rand::srand(5);
+        let drops: Vec<PickupKind> = (0..500).filter_map(|_| DestructibleKind::Barrel.roll_drop(1.0)).collect();
+
+        // About 60% of barrels drop something
+        assert!((200..400).contains(&drops.len()));
+        assert!(!drops.contains(&PickupKind::Treasure));
+    }
+}
@@ -0,0 +1,103 @@
+use macroquad::prelude::*;
+use nanoserde::DeJson;
+
+const AFFIXES_PATH: &str = "data/affixes.json";
+
+/// Numbers an elite affix changes, small and `Copy` so it lives in `EnemyData`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Affix {
+    pub color: Color,
+    pub speed: f32,
+    pub health: f32,
+    /// Flat damage reduction per hit.
+    pub armor: f32,
+    pub contact_damage: f32,
+    /// Fraction of the contact damage dealt that heals the enemy.
+    pub lifesteal: f32,
+    /// Regular enemies spawned where it dies.
+    pub split: usize,
+    pub explode_radius: f32,
+    pub explode_damage: f32,
+    /// Chance to drop a treasure instead of a health orb, before luck.
+    pub treasure_chance: f32,
+}
+
+/// An affix definition from `data/affixes.json`, whose `name` entries only label them for
+/// whoever edits the file.
+pub struct AffixDef {
+    /// Chance for a freshly spawned enemy to roll this affix.
+    pub chance: f32,
+    pub affix: Affix,
+}
+
+#[derive(DeJson)]
+struct AffixData {
+    chance: f32,
+    #[nserde(default)]
+    color: Vec<u8>,
+    #[nserde(default = "1.0")]
+    speed: f32,
+    #[nserde(default = "1.0")]
+    health: f32,
+    #[nserde(default)]
+    armor: f32,
+    #[nserde(default = "1.0")]
+    contact_damage: f32,
+    #[nserde(default)]
+    lifesteal: f32,
+    #[nserde(default)]
+    split: usize,
+    #[nserde(default)]
+    explode_radius: f32,
+    #[nserde(default)]
+    explode_damage: f32,
+    #[nserde(default = "0.15")]
+    treasure_chance: f32,
+}
+
+/// Loads the elite affixes, without the file enemies simply never roll one.
+pub async fn load_affixes() -> Vec<AffixDef> {
+    let json = match load_string(AFFIXES_PATH).await {
+        Ok(json) => json,
+        Err(_) => {
+            println!("Failed to load {}, elites are disabled", AFFIXES_PATH);
+            return Vec::new();
+        }
+    };
+
+    let data = match Vec::<AffixData>::deserialize_json(&json) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("Failed to parse {}: {}", AFFIXES_PATH, err);
+            return Vec::new();
+        }
+    };
+
+    data.into_iter()
+        .map(|data| AffixDef {
+            affix: Affix {
+                color: match data.color[..] {
+                    [r, g, b] => Color::from_rgba(r, g, b, 255),
+                    _ => GOLD,
+                },
+                speed: data.speed,
+                health: data.health,
+                armor: data.armor,
+                contact_damage: data.contact_damage,
+                lifesteal: data.lifesteal,
+                split: data.split,
+                explode_radius: data.explode_radius,
+                explode_damage: data.explode_damage,
+                treasure_chance: data.treasure_chance,
+            },
+            chance: data.chance,
+        })
+        .collect()
+}
+
+/// Rolls every affix in order, the first one that hits is used.
+pub fn roll_affix(defs: &[AffixDef]) -> Option<Affix> {
+    defs.iter()
+        .find(|def| rand::gen_range(0.0, 1.0) < def.chance)
+        .map(|def| def.affix)
+}
@@ -0,0 +1,246 @@
+use macroquad::prelude::*;
+
+use crate::combat::{Damageable, rect_overlaps_circle};
+use crate::enemies::EnemySystem;
+use crate::player::Player;
+use crate::projectiles::{FirePattern, ProjectileParams, ProjectilePool};
+
+/// Health fractions below which the boss enters its next phase.
+const PHASE_THRESHOLDS: [f32; 2] = [0.66, 0.33];
+const CONTACT_DAMAGE: f32 = 20.0;
+const SLAM_DAMAGE: f32 = 35.0;
+
+const NOVA_BULLET: ProjectileParams = ProjectileParams {
+    speed: 4.0,
+    damage: 15.0,
+    radius: 14.0,
+    lifetime: 4.0,
+    color: Color::new(0.8, 0.3, 1.0, 1.0),
+};
+
+/// Ground marker that deals damage once its delay runs out.
+struct Telegraph {
+    center: Vec2,
+    radius: f32,
+    delay: f32,
+    elapsed: f32,
+}
+
+impl Telegraph {
+    fn progress(&self) -> f32 {
+        (self.elapsed / self.delay).min(1.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BossAttack {
+    /// A single marker under the player.
+    Slam,
+    /// Markers in a ring around the player, leaving the center safe.
+    Ring,
+    /// Calls minions from the enemy pool.
+    Summon,
+    /// Bullets in every direction.
+    Nova,
+}
+
+pub struct Boss {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub health: f32,
+    pub max_health: f32,
+    pub name: String,
+    phase: usize,
+    attack_timer: f32,
+    telegraphs: Vec<Telegraph>,
+    texture: Option<Texture2D>,
+    current_frame: usize,
+    frame_timer: f32,
+    /// Seconds left of the flash shown when the phase changes.
+    enrage_timer: f32,
+    /// Area the boss is kept inside, `None` in the infinite world.
+    bounds: Option<Rect>,
+}
+
+impl Boss {
+    /// Bosses scale with the wave they show up in.
+    pub fn new(position: Vec2, wave: u32, texture: Option<Texture2D>, bounds: Option<Rect>) -> Self {
+        let max_health = 1200.0 * wave as f32 / 5.0;
+
+        Self {
+            position,
+            size: vec2(192.0, 192.0),
+            health: max_health,
+            max_health,
+            name: format!("Demon Lord of Wave {}", wave),
+            phase: 0,
+            attack_timer: 3.0,
+            telegraphs: Vec::new(),
+            texture,
+            current_frame: 0,
+            frame_timer: 0.0,
+            enrage_timer: 0.0,
+            bounds,
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.position + self.size / 2.0
+    }
+
+    /// Phase index, starting at 0 and going up as health drops.
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+
+    pub fn phase_thresholds(&self) -> &'static [f32] {
+        &PHASE_THRESHOLDS
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    pub fn update(&mut self, player: &mut Player, enemies: &mut EnemySystem, projectiles: &mut ProjectilePool) {
+        let dt = get_frame_time();
+        let player_center = player.center();
+
+        // Entering a new phase summons right away and speeds up the attacks
+        let phase = PHASE_THRESHOLDS
+            .iter()
+            .filter(|threshold| self.health / self.max_health < **threshold)
+            .count();
+        if phase > self.phase {
+            self.phase = phase;
+            self.enrage_timer = 0.6;
+            self.perform(BossAttack::Summon, player_center, enemies, projectiles);
+        }
+
+        let speed = 1.2 + self.phase as f32 * 0.6;
+        self.position += (player_center - self.center()).normalize_or_zero() * speed;
+        if let Some(bounds) = self.bounds {
+            let max = bounds.point() + bounds.size() - self.size;
+            self.position = self.position.clamp(bounds.point(), max.max(bounds.point()));
+        }
+
+        self.attack_timer -= dt;
+        if self.attack_timer <= 0.0 {
+            let attack = match (self.phase, rand::gen_range(0, 3)) {
+                (0, _) => BossAttack::Slam,
+                (_, 0) => BossAttack::Summon,
+                (1, 1) | (2, 1) => BossAttack::Nova,
+                (1, _) => BossAttack::Slam,
+                _ => BossAttack::Ring,
+            };
+
+            self.perform(attack, player_center, enemies, projectiles);
+            self.attack_timer = 3.5 - self.phase as f32 * 0.9;
+        }
+
+        for telegraph in self.telegraphs.iter_mut() {
+            telegraph.elapsed += dt;
+
+            if telegraph.elapsed >= telegraph.delay && player_center.distance(telegraph.center) <= telegraph.radius {
+                player.take_damage(SLAM_DAMAGE);
+            }
+        }
+        self.telegraphs.retain(|telegraph| telegraph.elapsed < telegraph.delay);
+
+        let player_rect = Rect::new(player.x, player.y, player.size, player.size);
+        if player_rect.overlaps(&Rect::new(self.position.x, self.position.y, self.size.x, self.size.y)) {
+            player.take_damage(CONTACT_DAMAGE);
+        }
+
+        self.enrage_timer = (self.enrage_timer - dt).max(0.0);
+        self.frame_timer += dt;
+        if self.frame_timer >= 0.2 {
+            self.frame_timer = 0.0;
+            self.current_frame = (self.current_frame + 1) % 4;
+        }
+    }
+
+    fn perform(&mut self, attack: BossAttack, target: Vec2, enemies: &mut EnemySystem, projectiles: &mut ProjectilePool) {
+        match attack {
+            BossAttack::Slam => self.telegraphs.push(Telegraph {
+                center: target,
+                radius: 140.0,
+                delay: 1.2,
+                elapsed: 0.0,
+            }),
+            BossAttack::Ring => {
+                let count = 6;
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    self.telegraphs.push(Telegraph {
+                        center: target + Vec2::from_angle(angle) * 220.0,
+                        radius: 110.0,
+                        delay: 1.0,
+                        elapsed: 0.0,
+                    });
+                }
+            }
+            BossAttack::Summon => {
+                enemies.spawn_at(self.center(), 150.0, 6 + self.phase * 4);
+            }
+            BossAttack::Nova => {
+                let count = 12 + self.phase * 4;
+                for i in 0..count {
+                    let direction = Vec2::from_angle(i as f32 / count as f32 * std::f32::consts::TAU);
+                    projectiles.fire(self.center(), target, FirePattern::Straight(direction), NOVA_BULLET);
+                }
+            }
+        }
+    }
+
+    /// Ground markers go under everything else, so this is drawn before the sprites.
+    pub fn draw_telegraphs(&self) {
+        for telegraph in &self.telegraphs {
+            let center = telegraph.center;
+            draw_circle(center.x, center.y, telegraph.radius, Color::new(1.0, 0.1, 0.1, 0.15));
+            draw_circle(center.x, center.y, telegraph.radius * telegraph.progress(), Color::new(1.0, 0.2, 0.1, 0.35));
+            draw_circle_lines(center.x, center.y, telegraph.radius, 3.0, Color::new(1.0, 0.3, 0.2, 0.8));
+        }
+    }
+
+    pub fn draw(&self) {
+        let tint = if self.enrage_timer > 0.0 && (get_time() * 20.0) as i64 % 2 == 0 {
+            WHITE
+        } else {
+            Color::from_rgba(200, 120, 255, 255)
+        };
+
+        match &self.texture {
+            Some(texture) => {
+                let frame = 64.0;
+                let params = DrawTextureParams {
+                    dest_size: Some(self.size),
+                    source: Some(Rect {
+                        x: self.current_frame as f32 * frame,
+                        y: texture.height(),
+                        w: frame,
+                        h: -frame,
+                    }),
+                    ..Default::default()
+                };
+                draw_texture_ex(texture, self.position.x, self.position.y, tint, params);
+            }
+            None => draw_rectangle(self.position.x, self.position.y, self.size.x, self.size.y, tint),
+        }
+    }
+}
+
+impl Damageable for Boss {
+    fn targets_in(&self, center: Vec2, radius: f32) -> Vec<usize> {
+        if !self.is_dead() && rect_overlaps_circle(self.position, self.size, center, radius) {
+            vec![0]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn apply_damage(&mut self, _index: usize, amount: f32) -> f32 {
+        let damage = amount.min(self.health);
+        self.health -= damage;
+        damage
+    }
+}
@@ -1,11 +1,48 @@
 use macroquad::prelude::*;
 use crate::strategies::MovementStrategy;
-use crate::constants::{WORLD_WIDTH, WORLD_HEIGHT};
 use crate::player::Player;
 use crate::strategies::CollisionStrategy;
+use crate::combat::{Damageable, rect_overlaps_circle};
+use crate::projectiles::{FirePattern, ProjectileParams, ProjectilePool};
+use crate::enemies::status::{StatusEffect, StatusEffects};
+use crate::world::{Obstacles, World};
+use crate::materials::Materials;
+use crate::enemies::affixes::{Affix, AffixDef, load_affixes, roll_affix};
 use std::cmp;
 
-#[allow(dead_code)]
+const ENEMY_HEALTH: f32 = 30.0;
+const ENEMY_SIZE: f32 = 64.0;
+const ELITE_SIZE: f32 = 84.0;
+/// Size of a frame in the sprite sheet, independent of the size enemies are drawn at.
+const FRAME_SIZE: f32 = 64.0;
+/// Seconds between an explosive elite dying and its blast going off.
+const EXPLOSION_FUSE: f32 = 0.6;
+/// Chance for a spawned enemy to be a ranged attacker.
+const RANGED_CHANCE: f32 = 0.15;
+/// Ranged enemies only shoot at players closer than this.
+const FIRE_RANGE: f32 = 700.0;
+/// How far from a spawn point enemies appear.
+const SPAWN_SPREAD: f32 = 300.0;
+/// Seconds a hit enemy flashes white.
+const HIT_FLASH: f32 = 0.12;
+/// Seconds a killed enemy takes to dissolve away.
+const DISSOLVE_TIME: f32 = 0.4;
+/// Distance over which enemies at the edge of sight fade out.
+const SIGHT_FADE: f32 = 150.0;
+/// Room between the view and the relocation radius, so recycled enemies land off screen
+/// without drifting straight back out of range.
+const RELOCATION_MARGIN: f32 = 300.0;
+/// Directions tried before giving up on recycling an enemy this frame.
+const RELOCATION_ATTEMPTS: usize = 8;
+
+const ENEMY_BULLET: ProjectileParams = ProjectileParams {
+    speed: 5.0,
+    damage: 12.0,
+    radius: 9.0,
+    lifetime: 3.0,
+    color: Color::new(1.0, 0.35, 0.2, 1.0),
+};
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum EnemyStatus {
     Pending,
@@ -13,6 +50,24 @@ pub enum EnemyStatus {
     Dead,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum EnemyKind {
+    /// Chases the player and hurts on contact.
+    Melee,
+    /// Keeps its distance and shoots.
+    Ranged,
+}
+
+impl EnemyKind {
+    fn roll() -> Self {
+        if rand::gen_range(0.0, 1.0) < RANGED_CHANCE {
+            EnemyKind::Ranged
+        } else {
+            EnemyKind::Melee
+        }
+    }
+}
+
 pub enum PositionOverlap {
     Behind,
     InFront,
@@ -22,6 +77,46 @@ pub enum PositionOverlap {
 pub struct EnemyData {
     pub status: EnemyStatus,
     pub last_movement: Vec2, // Track movement direction for flipping
+    pub health: f32,
+    /// Health at spawn, healing never goes past it.
+    pub max_health: f32,
+    pub kind: EnemyKind,
+    /// Seconds until a ranged enemy can shoot again.
+    pub attack_timer: f32,
+    pub effects: StatusEffects,
+    /// Set for elites.
+    pub affix: Option<Affix>,
+    /// Seconds left of the white flash after a hit.
+    pub flash: f32,
+    /// Seconds left of the death dissolve, dead enemies are drawn until it runs out.
+    pub dissolve: f32,
+}
+
+impl EnemyData {
+    fn spawned(affix: Option<Affix>) -> Self {
+        let health = ENEMY_HEALTH * affix.map_or(1.0, |affix| affix.health);
+
+        Self {
+            status: EnemyStatus::Live,
+            last_movement: Vec2::new(1.0, 0.0),
+            health,
+            max_health: health,
+            kind: EnemyKind::roll(),
+            attack_timer: rand::gen_range(1.0, 3.0),
+            effects: StatusEffects::default(),
+            affix,
+            flash: 0.0,
+            dissolve: 0.0,
+        }
+    }
+}
+
+/// Blast left behind by an explosive elite, telegraphed like the boss slams.
+struct Explosion {
+    center: Vec2,
+    radius: f32,
+    damage: f32,
+    fuse: f32,
 }
 
 pub struct EnemySystem {
@@ -29,7 +124,22 @@ pub struct EnemySystem {
     pub sizes: Vec<Vec2>,
     pub data: Vec<EnemyData>,
     movement_strategy: Box<dyn MovementStrategy>,
+    ranged_strategy: Box<dyn MovementStrategy>,
     collision_strategy: Box<dyn CollisionStrategy>,
+    affixes: Vec<AffixDef>,
+    /// Elites killed since the last update, with their center.
+    elite_deaths: Vec<(Vec2, Affix)>,
+    /// Centers of every enemy killed since the last `take_deaths`.
+    deaths: Vec<Vec2>,
+    explosions: Vec<Explosion>,
+    /// Live enemies farther than this from the player get moved back near it.
+    relocation_radius: f32,
+    /// Area enemies spawn in when there are no spawn points.
+    area: Rect,
+    /// Whether enemies are kept inside the area, off in the infinite world.
+    bounded: bool,
+    /// Where enemies spawn, empty to spawn anywhere in the area.
+    spawn_points: Vec<Vec2>,
     time: f32,
     chunk_index: usize,
     max_number_of_chunks: usize,
@@ -42,7 +152,9 @@ pub struct EnemySystem {
 impl EnemySystem {
     pub async fn new(
         count: usize, 
+        area: Rect,
         movement_strategy: Box<dyn MovementStrategy>,
+        ranged_strategy: Box<dyn MovementStrategy>,
         collision_strategy: Box<dyn CollisionStrategy>,
     ) -> Self {
         
@@ -54,25 +166,31 @@ impl EnemySystem {
             }
         };
 
-        let positions = (0..count)
-            .map(|_| vec2(
-                rand::gen_range(0.0, WORLD_WIDTH),
-                rand::gen_range(0.0, WORLD_HEIGHT),
-            ))
+        let affixes = load_affixes().await;
+
+        let data: Vec<EnemyData> = (0..count)
+            .map(|_| EnemyData { 
+                status: EnemyStatus::Pending,
+                ..EnemyData::spawned(roll_affix(&affixes))
+            })
             .collect();
-            
-        let sizes = vec![vec2(64.0, 64.0); count];
-        let data = vec![EnemyData { 
-            status: EnemyStatus::Pending,
-            last_movement: Vec2::new(1.0, 0.0) 
-        }; count];
+        let sizes = data.iter().map(|data| Vec2::splat(size_for(data.affix))).collect();
 
         EnemySystem {
-            positions,
+            positions: vec![Vec2::ZERO; count],
             sizes,
             data,
             movement_strategy,
+            ranged_strategy,
             collision_strategy,
+            affixes,
+            elite_deaths: Vec::new(),
+            deaths: Vec::new(),
+            explosions: Vec::new(),
+            relocation_radius: 2000.0,
+            area,
+            bounded: true,
+            spawn_points: Vec::new(),
             time: 0.0,
             chunk_index: 0,
             max_number_of_chunks: 4,
@@ -83,16 +201,166 @@ impl EnemySystem {
         }
     }
     
+    pub fn relocation_radius(mut self, radius: f32) -> Self {
+        self.relocation_radius = radius;
+        self
+    }
+
+    /// Lets enemies leave the area, for worlds without edges.
+    pub fn unbounded(mut self) -> Self {
+        self.bounded = false;
+        self
+    }
+
+    pub fn spawn_points(mut self, points: Vec<Vec2>) -> Self {
+        self.spawn_points = points;
+        self
+    }
+
+    /// Brings in the whole pool, with the affixes rolled when it was created.
     pub fn spawn_all(&mut self) {
-        for data in &mut self.data {
-            data.status = EnemyStatus::Live;
+        for i in 0..self.positions.len() {
+            self.positions[i] = self.spawn_position(self.sizes[i]);
+            self.data[i].status = EnemyStatus::Live;
         }
     }
+
+    /// A random spot near a spawn point, or anywhere without spawn points.
+    fn spawn_position(&self, size: Vec2) -> Vec2 {
+        let position = match self.spawn_points.len() {
+            0 => vec2(
+                rand::gen_range(self.area.left(), self.area.right()),
+                rand::gen_range(self.area.top(), self.area.bottom()),
+            ),
+            count => {
+                let point = self.spawn_points[rand::gen_range(0, count)];
+                point + vec2(rand::gen_range(-SPAWN_SPREAD, SPAWN_SPREAD), rand::gen_range(-SPAWN_SPREAD, SPAWN_SPREAD))
+            }
+        };
+
+        self.clamp(position, size)
+    }
+
+    /// Moves the area enemies spawn in, for worlds that stream in around the player.
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    fn clamp(&self, position: Vec2, size: Vec2) -> Vec2 {
+        clamp_into(self.bounds(), position, size)
+    }
+
+    fn bounds(&self) -> Option<Rect> {
+        self.bounded.then_some(self.area)
+    }
     
-    pub fn update(&mut self, target_pos: Vec2, player: &mut Player) {
+    /// Brings back up to `count` dead enemies at random spots around `center`.
+    ///
+    /// The pool never grows, so this returns how many could actually be spawned.
+    pub fn spawn_at(&mut self, center: Vec2, spread: f32, count: usize) -> usize {
+        self.spawn(center, spread, count, true)
+    }
+
+    fn spawn(&mut self, center: Vec2, spread: f32, count: usize, allow_elites: bool) -> usize {
+        let mut spawned = 0;
+
+        for i in 0..self.data.len() {
+            if spawned == count {
+                break;
+            }
+            if self.data[i].status == EnemyStatus::Live {
+                continue;
+            }
+
+            let offset = vec2(rand::gen_range(-spread, spread), rand::gen_range(-spread, spread));
+            let affix = if allow_elites { roll_affix(&self.affixes) } else { None };
+            self.data[i] = EnemyData::spawned(affix);
+            self.sizes[i] = Vec2::splat(size_for(affix));
+            self.positions[i] = self.clamp(center + offset, self.sizes[i]);
+            spawned += 1;
+        }
+
+        spawned
+    }
+
+    /// Brings back up to `count` dead enemies around the spawn points, or anywhere in the
+    /// world without them.
+    pub fn respawn(&mut self, count: usize) -> usize {
+        if self.spawn_points.is_empty() {
+            let center = self.area.center();
+            return self.spawn_at(center, self.area.w.max(self.area.h) / 2.0, count);
+        }
+
+        let mut spawned = 0;
+        while spawned < count {
+            let point = self.spawn_points[rand::gen_range(0, self.spawn_points.len())];
+            match self.spawn_at(point, SPAWN_SPREAD, 1) {
+                0 => break,
+                _ => spawned += 1,
+            }
+        }
+
+        spawned
+    }
+
+    pub fn live_count(&self) -> usize {
+        self.data.iter().filter(|data| data.status == EnemyStatus::Live).count()
+    }
+
+    /// Center of the closest live enemy within `range` of `point`.
+    pub fn nearest(&self, point: Vec2, range: f32) -> Option<Vec2> {
+        (0..self.positions.len())
+            .filter(|&i| self.data[i].status == EnemyStatus::Live)
+            .map(|i| self.positions[i] + self.sizes[i] / 2.0)
+            .filter(|center| center.distance_squared(point) <= range * range)
+            .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+    }
+
+    pub fn texture(&self) -> Option<&Texture2D> {
+        self.texture.as_ref()
+    }
+
+    /// Recycles enemies that drifted beyond the relocation radius, placing them just outside
+    /// the view in the direction the player is heading so the pressure stays in front of them.
+    ///
+    /// `travel` is a world-space direction, zero when standing still, and `view_radius` is the
+    /// distance from the player to the corners of the view.
+    pub fn relocate_far(&mut self, player_center: Vec2, travel: Vec2, view_radius: f32) {
+        // Never recycle enemies that could still be on screen
+        let radius = self.relocation_radius.max(view_radius + RELOCATION_MARGIN);
+        let radius_squared = radius * radius;
+        let heading = travel.normalize_or_zero();
+
+        for i in 0..self.positions.len() {
+            let center = self.positions[i] + self.sizes[i] / 2.0;
+            if self.data[i].status != EnemyStatus::Live || center.distance_squared(player_center) <= radius_squared {
+                continue;
+            }
+
+            // Left where it is when every spot was pulled back into view, it's out of sight anyway
+            if let Some(position) = out_of_view_position(player_center, heading, view_radius, radius, self.sizes[i], self.bounds()) {
+                self.positions[i] = position;
+                self.data[i].effects = StatusEffects::default();
+            }
+        }
+    }
+
+    /// Moves, animates and resolves every enemy, returning the elites that died since last
+    /// time so the caller can drop their loot.
+    pub fn update(
+        &mut self,
+        target_pos: Vec2,
+        world: &World,
+        player: &mut Player,
+        projectiles: &mut ProjectilePool,
+    ) -> Vec<(Vec2, Affix)> {
 
-        self.update_movement(target_pos);
+        self.update_status_effects(&world.obstacles);
+        self.movement_strategy.prepare(target_pos, &world.nav);
+        self.ranged_strategy.prepare(target_pos, &world.nav);
+        self.update_movement(target_pos, &world.obstacles);
         self.update_animation_frame();
+        self.update_ranged_attacks(player.center(), projectiles);
 
         // Here the compiler allow us to use the mutable reference to self.data
         self.collision_strategy.check_collisions(
@@ -101,9 +369,59 @@ impl EnemySystem {
             &mut self.data,
             player
         );
+
+        self.update_explosions(player);
+
+        let elite_deaths = std::mem::take(&mut self.elite_deaths);
+        for (center, affix) in &elite_deaths {
+            if affix.split > 0 {
+                self.spawn(*center, 40.0, affix.split, false);
+            }
+            if affix.explode_radius > 0.0 {
+                self.explosions.push(Explosion {
+                    center: *center,
+                    radius: affix.explode_radius,
+                    damage: affix.explode_damage,
+                    fuse: EXPLOSION_FUSE,
+                });
+            }
+        }
+
+        elite_deaths
+    }
+
+    /// Enemies killed by anything since the last call, for death effects.
+    pub fn take_deaths(&mut self) -> Vec<Vec2> {
+        std::mem::take(&mut self.deaths)
     }
 
-    fn update_movement(&mut self, target_pos: Vec2) {
+    fn update_explosions(&mut self, player: &mut Player) {
+        let dt = get_frame_time();
+        let player_center = player.center();
+
+        for explosion in self.explosions.iter_mut() {
+            explosion.fuse -= dt;
+
+            if explosion.fuse <= 0.0 && player_center.distance(explosion.center) <= explosion.radius {
+                player.take_damage(explosion.damage);
+            }
+        }
+
+        self.explosions.retain(|explosion| explosion.fuse > 0.0);
+    }
+
+    /// Pending explosions, drawn under the sprites like the boss telegraphs.
+    pub fn draw_explosions(&self) {
+        for explosion in &self.explosions {
+            let progress = 1.0 - (explosion.fuse / EXPLOSION_FUSE).clamp(0.0, 1.0);
+            let center = explosion.center;
+
+            draw_circle(center.x, center.y, explosion.radius * progress, Color::new(1.0, 0.5, 0.1, 0.35));
+            draw_circle_lines(center.x, center.y, explosion.radius, 3.0, Color::new(1.0, 0.6, 0.2, 0.8));
+        }
+    }
+
+    fn update_movement(&mut self, target_pos: Vec2, obstacles: &Obstacles) {
         self.time += get_frame_time();
         self.chunk_index = if self.chunk_index < self.max_number_of_chunks - 1 {
             self.chunk_index + 1
@@ -119,16 +437,25 @@ impl EnemySystem {
         let all_positions = self.positions.clone();
     
         for i in start..end {
-            if self.data[i].status == EnemyStatus::Live {
+            // Knocked back enemies were already moved this frame
+            if self.data[i].status == EnemyStatus::Live && !self.data[i].effects.is_knocked_back() {
                 let prev_pos = self.positions[i];
     
-                self.movement_strategy.move_enemy(
+                let strategy = match self.data[i].kind {
+                    EnemyKind::Melee => &self.movement_strategy,
+                    EnemyKind::Ranged => &self.ranged_strategy,
+                };
+
+                strategy.move_enemy(
                     &mut self.positions[i],
                     target_pos,
                     current_time,
                     i,
                     &all_positions,
+                    self.data[i].effects.speed_multiplier() * self.data[i].affix.map_or(1.0, |affix| affix.speed),
                 );
+                let moved = self.clamp(self.positions[i], self.sizes[i]);
+                self.positions[i] = obstacles.slide(prev_pos, moved, self.sizes[i]);
     
                 let movement = self.positions[i] - prev_pos;
                 if movement.length_squared() > 0.0 {
@@ -138,15 +465,110 @@ impl EnemySystem {
         }
     }
 
+    /// Ticks damage over time and applies knockback, for every enemy every frame.
+    fn update_status_effects(&mut self, obstacles: &Obstacles) {
+        let dt = get_frame_time();
+
+        for i in 0..self.positions.len() {
+            if self.data[i].status != EnemyStatus::Live {
+                continue;
+            }
+
+            let damage = self.data[i].effects.update(dt);
+            if damage > 0.0 {
+                self.apply_damage(i, damage);
+            }
+
+            if let Some(step) = self.data[i].effects.take_knockback() {
+                let pushed = self.clamp(self.positions[i] + step, self.sizes[i]);
+                self.positions[i] = obstacles.slide(self.positions[i], pushed, self.sizes[i]);
+            }
+        }
+    }
+
+    fn update_ranged_attacks(&mut self, target: Vec2, projectiles: &mut ProjectilePool) {
+        let dt = get_frame_time();
+
+        for i in 0..self.positions.len() {
+            let data = &mut self.data[i];
+            if data.status != EnemyStatus::Live || data.kind != EnemyKind::Ranged || data.effects.is_frozen() {
+                continue;
+            }
+
+            data.attack_timer -= dt;
+            let origin = self.positions[i] + self.sizes[i] / 2.0;
+            if data.attack_timer > 0.0 || origin.distance(target) > FIRE_RANGE {
+                continue;
+            }
+
+            // Every ranged enemy sticks to one pattern so the player can learn to read them
+            let pattern = match i % 4 {
+                0 => FirePattern::Spread { count: 3, angle: 0.5 },
+                1 => FirePattern::Homing { turn_rate: 1.5 },
+                2 => FirePattern::Aimed,
+                // Locked to the axis closest to the target, side-stepping dodges it
+                _ => {
+                    let to_target = target - origin;
+                    if to_target.x.abs() > to_target.y.abs() {
+                        FirePattern::Straight(vec2(to_target.x.signum(), 0.0))
+                    } else {
+                        FirePattern::Straight(vec2(0.0, to_target.y.signum()))
+                    }
+                }
+            };
+
+            projectiles.fire(origin, target, pattern, ENEMY_BULLET);
+            data.attack_timer = rand::gen_range(2.0, 3.5);
+        }
+    }
+
     fn update_animation_frame(&mut self) {
-        self.frame_timer += get_frame_time();
+        let dt = get_frame_time();
+        for data in self.data.iter_mut() {
+            data.flash = (data.flash - dt).max(0.0);
+            data.dissolve = (data.dissolve - dt).max(0.0);
+        }
+
+        self.frame_timer += dt;
         if self.frame_timer >= self.frame_duration {
             self.frame_timer = 0.0;
             self.current_frame = (self.current_frame + 1) % 4;
         }
     }
     
-    pub fn draw(&self, target_pos: Vec2, overlap: PositionOverlap) {
+    /// Whether an enemy is still drawn, live or dissolving away.
+    fn is_visible(&self, index: usize) -> bool {
+        let data = &self.data[index];
+        data.status == EnemyStatus::Live || (data.status == EnemyStatus::Dead && data.dissolve > 0.0)
+    }
+
+    /// Centers and colors of the live elites, which glow in the dark.
+    pub fn elite_glows(&self) -> impl Iterator<Item = (Vec2, Color)> + '_ {
+        (0..self.positions.len())
+            .filter(|&i| self.data[i].status == EnemyStatus::Live)
+            .filter_map(|i| self.data[i].affix.map(|affix| (self.positions[i] + self.sizes[i] / 2.0, affix.color)))
+    }
+
+    /// Draws a sprite flashing after a hit, or dissolving if its enemy just died.
+    ///
+    /// `draw` gets the opacity to draw with.
+    fn draw_with_feedback(&self, index: usize, materials: &Materials, fade: f32, draw: impl FnOnce(f32)) {
+        let data = &self.data[index];
+        let draw = |alpha: f32| draw(alpha * fade);
+
+        if data.status == EnemyStatus::Dead {
+            materials.draw_dissolving(1.0 - data.dissolve / DISSOLVE_TIME, draw);
+        } else {
+            let rect = Rect::new(self.positions[index].x, self.positions[index].y, self.sizes[index].x, self.sizes[index].y);
+            materials.draw_flashing(data.flash / HIT_FLASH, rect, || draw(1.0));
+        }
+    }
+
+    /// Draws the enemies on one side of the player, those farther than `sight` fade into
+    /// the dark.
+    pub fn draw(&self, target_pos: Vec2, overlap: PositionOverlap, sight: f32, materials: &Materials) {
+        let fade = |i: usize| ((sight - self.positions[i].distance(target_pos)) / SIGHT_FADE).clamp(0.0, 1.0);
+
         match &self.texture {
             Some(texture) => {
 
@@ -165,47 +587,201 @@ impl EnemySystem {
                 filtered_indices.sort_by(|&a, &b| self.positions[b].y.partial_cmp(&self.positions[a].y).unwrap());
 
                 for &i in &filtered_indices {
-                    if self.data[i].status == EnemyStatus::Live {
+                    if self.is_visible(i) && fade(i) > 0.0 {
+                        if let (Some(affix), EnemyStatus::Live) = (self.data[i].affix, self.data[i].status) {
+                            draw_elite_outline(self.positions[i], self.sizes[i], affix.color);
+                        }
                         
                         let flip_x = self.positions[i].x > target_pos.x;
-                        let frame_width = self.sizes[i].x;
-                        let frame_height = self.sizes[i].y;
+                        let frame_width = FRAME_SIZE;
+                        let frame_height = FRAME_SIZE;
+
+                        // Frozen enemies hold the pose they were frozen in
+                        let effects = &self.data[i].effects;
+                        let frame = if effects.is_frozen() { effects.frozen_frame } else { self.current_frame };
                         
                         let params = DrawTextureParams {
                             dest_size: Some(self.sizes[i]),
                             flip_x,
                             source: Some(Rect {
-                                x: self.current_frame as f32 * frame_width,
+                                x: frame as f32 * frame_width,
                                 y: texture.height(),
                                 w: frame_width,
                                 h: -frame_height,
                             }),
                             ..Default::default()
                         };
-                        draw_texture_ex(
-                            texture,
-                            self.positions[i].x,
-                            self.positions[i].y,
-                            WHITE,
-                            params
-                        );
+                        let color = effects.tint().unwrap_or(tint(&self.data[i]));
+                        self.draw_with_feedback(i, materials, fade(i), |alpha| {
+                            draw_texture_ex(
+                                texture,
+                                self.positions[i].x,
+                                self.positions[i].y,
+                                Color { a: color.a * alpha, ..color },
+                                params
+                            );
+                        });
                     }
                 }
             }
             None => {
                 // Fallback to rectangles if no texture
                 for i in 0..self.positions.len() {
-                    if self.data[i].status == EnemyStatus::Live {
-                        draw_rectangle(
-                            self.positions[i].x,
-                            self.positions[i].y,
-                            self.sizes[i].x,
-                            self.sizes[i].y,
-                            RED
-                        );
+                    if self.is_visible(i) && fade(i) > 0.0 {
+                        let color = match self.data[i].kind {
+                            EnemyKind::Melee => RED,
+                            EnemyKind::Ranged => ORANGE,
+                        };
+                        self.draw_with_feedback(i, materials, fade(i), |alpha| {
+                            draw_rectangle(
+                                self.positions[i].x,
+                                self.positions[i].y,
+                                self.sizes[i].x,
+                                self.sizes[i].y,
+                                Color { a: alpha, ..color },
+                            );
+                        });
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn clamp_into(bounds: Option<Rect>, position: Vec2, size: Vec2) -> Vec2 {
+    let Some(area) = bounds else {
+        return position;
+    };
+
+    let max = (area.point() + area.size() - size).max(area.point());
+    position.clamp(area.point(), max)
+}
+
+/// A top-left corner just outside the view and inside `radius`, or `None` when the bounds
+/// keep pulling the spot back into view.
+fn out_of_view_position(
+    player_center: Vec2,
+    heading: Vec2,
+    view_radius: f32,
+    radius: f32,
+    size: Vec2,
+    bounds: Option<Rect>,
+) -> Option<Vec2> {
+    let half_extent = size.length() / 2.0;
+
+    for attempt in 0..RELOCATION_ATTEMPTS {
+        // Spread them over a cone ahead of the player, falling back to all around when
+        // standing still or when the cone runs into the edge of the world
+        let direction = if heading == Vec2::ZERO || attempt >= RELOCATION_ATTEMPTS / 2 {
+            Vec2::from_angle(rand::gen_range(0.0, std::f32::consts::TAU))
+        } else {
+            Vec2::from_angle(heading.to_angle() + rand::gen_range(-1.0, 1.0))
+        };
+
+        let distance = (view_radius + half_extent + rand::gen_range(0.0, 150.0)).min(radius - half_extent);
+        let position = clamp_into(bounds, player_center + direction * distance - size / 2.0, size);
+        let center_distance = (position + size / 2.0).distance(player_center);
+
+        if center_distance > view_radius + half_extent && center_distance <= radius {
+            return Some(position);
+        }
+    }
+
+    None
+}
+
+fn size_for(affix: Option<Affix>) -> f32 {
+    if affix.is_some() { ELITE_SIZE } else { ENEMY_SIZE }
+}
+
+fn tint(data: &EnemyData) -> Color {
+    match (data.affix, data.kind) {
+        (Some(affix), _) => affix.color,
+        (None, EnemyKind::Melee) => WHITE,
+        (None, EnemyKind::Ranged) => Color::from_rgba(140, 200, 255, 255),
+    }
+}
+
+/// Glowing ring around an elite, visible even while a status effect recolors the sprite.
+fn draw_elite_outline(position: Vec2, size: Vec2, color: Color) {
+    let center = position + size / 2.0;
+    let pulse = 0.6 + (get_time() * 5.0).sin() as f32 * 0.2;
+
+    draw_circle_lines(center.x, center.y, size.x * 0.6, 3.0, Color::new(color.r, color.g, color.b, pulse));
+}
+
+impl Damageable for EnemySystem {
+    fn targets_in(&self, center: Vec2, radius: f32) -> Vec<usize> {
+        (0..self.positions.len())
+            .filter(|&i| {
+                self.data[i].status == EnemyStatus::Live
+                    && rect_overlaps_circle(self.positions[i], self.sizes[i], center, radius)
+            })
+            .collect()
+    }
+
+    fn apply_effect(&mut self, index: usize, effect: StatusEffect) {
+        if self.data[index].status == EnemyStatus::Live {
+            self.data[index].effects.apply(effect, self.current_frame);
+        }
+    }
+
+    fn apply_damage(&mut self, index: usize, amount: f32) -> f32 {
+        let data = &mut self.data[index];
+        let armor = data.affix.map_or(0.0, |affix| affix.armor);
+        let damage = (amount - armor).max(1.0).min(data.health);
+        data.health -= damage;
+        data.flash = HIT_FLASH;
+
+        if data.health <= 0.0 {
+            data.status = EnemyStatus::Dead;
+            data.dissolve = DISSOLVE_TIME;
+            let center = self.positions[index] + self.sizes[index] / 2.0;
+            self.deaths.push(center);
+            if let Some(affix) = data.affix {
+                self.elite_deaths.push((center, affix));
+            }
+        }
+
+        damage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::splat(ENEMY_SIZE);
+
+    #[test]
+    fn relocated_enemies_stay_inside_the_radius_on_large_views() {
+        // A view radius past the relocation radius must not place enemies out of range again
+        for _ in 0..100 {
+            let position = out_of_view_position(Vec2::ZERO, Vec2::X, 2500.0, 2500.0 + RELOCATION_MARGIN, SIZE, None).unwrap();
+            let distance = (position + SIZE / 2.0).length();
+            assert!(distance > 2500.0 && distance <= 2500.0 + RELOCATION_MARGIN);
+        }
+    }
+
+    #[test]
+    fn relocation_near_the_edge_never_lands_in_view() {
+        // Heading straight into the edge of the world, the cone alone would be clamped on screen
+        let bounds = Rect::new(0.0, 0.0, 4000.0, 4000.0);
+        let player = vec2(3950.0, 2000.0);
+        let mut placed = 0;
+        for _ in 0..100 {
+            if let Some(position) = out_of_view_position(player, Vec2::X, 800.0, 1800.0, SIZE, Some(bounds)) {
+                assert!((position + SIZE / 2.0).distance(player) > 800.0);
+                assert!(bounds.contains(position));
+                placed += 1;
+            }
+        }
+        assert!(placed > 0);
+    }
+
+    #[test]
+    fn relocation_gives_up_when_the_world_fits_in_view() {
+        let bounds = Rect::new(0.0, 0.0, 500.0, 500.0);
+        assert_eq!(out_of_view_position(vec2(250.0, 250.0), Vec2::ZERO, 800.0, 1800.0, SIZE, Some(bounds)), None);
+    }
+}
@@ -1,5 +1,10 @@
+mod affixes;
+mod boss;
 mod enemy_system;
+mod status;
+pub use boss::Boss;
+pub use status::StatusEffect;
 pub use enemy_system::EnemySystem;
 pub use enemy_system::PositionOverlap;
 pub use enemy_system::EnemyStatus;
-pub use enemy_system::EnemyData;
\ No newline at end of file
+pub use enemy_system::EnemyData;
@@ -0,0 +1,241 @@
+use macroquad::prelude::*;
+
+/// Seconds between two damage-over-time ticks.
+const TICK_INTERVAL: f32 = 0.5;
+/// Frames a knockback overrides steering for.
+const KNOCKBACK_TICKS: u8 = 6;
+const MAX_POISON_STACKS: u8 = 5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StatusEffect {
+    /// Multiplies speed by `1 - amount`, the strongest slow wins and durations refresh.
+    Slow { amount: f32, duration: f32 },
+    /// Damage per second, durations add up so repeated hits keep it burning longer.
+    Burn { dps: f32, duration: f32 },
+    /// Stops movement and animation, durations refresh.
+    Freeze { duration: f32 },
+    /// Damage per second per stack, every hit adds a stack and refreshes the duration.
+    Poison { dps: f32, duration: f32 },
+    /// Pushes the enemy along a world-space impulse, per frame.
+    Knockback { impulse: Vec2 },
+}
+
+/// Status effects currently on an enemy, small and `Copy` so it lives in `EnemyData`.
+#[derive(Clone, Copy, Default)]
+pub struct StatusEffects {
+    slow_amount: f32,
+    slow_remaining: f32,
+    burn_dps: f32,
+    burn_remaining: f32,
+    freeze_remaining: f32,
+    /// Animation frame the enemy was frozen on.
+    pub frozen_frame: usize,
+    poison_dps: f32,
+    poison_stacks: u8,
+    poison_remaining: f32,
+    knockback: Vec2,
+    knockback_ticks: u8,
+    tick_timer: f32,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect, current_frame: usize) {
+        match effect {
+            StatusEffect::Slow { amount, duration } => {
+                self.slow_amount = self.slow_amount.max(amount.clamp(0.0, 1.0));
+                self.slow_remaining = self.slow_remaining.max(duration);
+            }
+            StatusEffect::Burn { dps, duration } => {
+                self.burn_dps = self.burn_dps.max(dps);
+                self.burn_remaining += duration;
+            }
+            StatusEffect::Freeze { duration } => {
+                if !self.is_frozen() {
+                    self.frozen_frame = current_frame;
+                }
+                self.freeze_remaining = self.freeze_remaining.max(duration);
+            }
+            StatusEffect::Poison { dps, duration } => {
+                self.poison_dps = self.poison_dps.max(dps);
+                self.poison_stacks = (self.poison_stacks + 1).min(MAX_POISON_STACKS);
+                self.poison_remaining = duration;
+            }
+            StatusEffect::Knockback { impulse } => {
+                self.knockback = impulse;
+                self.knockback_ticks = KNOCKBACK_TICKS;
+            }
+        }
+    }
+
+    /// Counts every effect down and returns the damage-over-time dealt this frame.
+    pub fn update(&mut self, dt: f32) -> f32 {
+        self.slow_remaining = (self.slow_remaining - dt).max(0.0);
+        self.freeze_remaining = (self.freeze_remaining - dt).max(0.0);
+        if self.slow_remaining == 0.0 {
+            self.slow_amount = 0.0;
+        }
+
+        let dps = self.damage_per_second();
+
+        self.burn_remaining = (self.burn_remaining - dt).max(0.0);
+        self.poison_remaining = (self.poison_remaining - dt).max(0.0);
+        if self.burn_remaining == 0.0 {
+            self.burn_dps = 0.0;
+        }
+        if self.poison_remaining == 0.0 {
+            self.poison_dps = 0.0;
+            self.poison_stacks = 0;
+        }
+
+        if dps == 0.0 {
+            self.tick_timer = 0.0;
+            return 0.0;
+        }
+
+        // Damage lands in discrete ticks rather than a little every frame
+        self.tick_timer += dt;
+        if self.tick_timer >= TICK_INTERVAL {
+            self.tick_timer -= TICK_INTERVAL;
+            return dps * TICK_INTERVAL;
+        }
+
+        0.0
+    }
+
+    fn damage_per_second(&self) -> f32 {
+        let burn = if self.burn_remaining > 0.0 { self.burn_dps } else { 0.0 };
+        let poison = if self.poison_remaining > 0.0 { self.poison_dps * self.poison_stacks as f32 } else { 0.0 };
+        burn + poison
+    }
+
+    /// Factor for the enemy speed, handed to the movement strategies.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_frozen() {
+            0.0
+        } else {
+            1.0 - self.slow_amount
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.freeze_remaining > 0.0
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.burn_remaining > 0.0
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poison_remaining > 0.0
+    }
+
+    pub fn is_knocked_back(&self) -> bool {
+        self.knockback_ticks > 0
+    }
+
+    /// Takes this frame's knockback step, `None` once the impulse is spent.
+    pub fn take_knockback(&mut self) -> Option<Vec2> {
+        if self.knockback_ticks == 0 {
+            return None;
+        }
+
+        self.knockback_ticks -= 1;
+        Some(self.knockback * (self.knockback_ticks as f32 + 1.0) / KNOCKBACK_TICKS as f32)
+    }
+
+    /// Color the enemy sprite is multiplied by to show its strongest effect.
+    pub fn tint(&self) -> Option<Color> {
+        if self.is_frozen() {
+            Some(Color::from_rgba(150, 220, 255, 255))
+        } else if self.is_burning() {
+            Some(Color::from_rgba(255, 160, 90, 255))
+        } else if self.is_poisoned() {
+            Some(Color::from_rgba(150, 255, 120, 255))
+        } else if self.slow_amount > 0.0 {
+            Some(Color::from_rgba(180, 180, 255, 255))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strongest_slow_wins_and_wears_off() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Slow { amount: 0.5, duration: 1.0 }, 0);
+        effects.apply(StatusEffect::Slow { amount: 0.2, duration: 2.0 }, 0);
+        assert_eq!(effects.speed_multiplier(), 0.5);
+
+        effects.update(1.5);
+        assert_eq!(effects.speed_multiplier(), 0.5);
+        effects.update(0.5);
+        assert_eq!(effects.speed_multiplier(), 1.0);
+        assert!(effects.tint().is_none());
+    }
+
+    #[test]
+    fn freeze_stops_movement_on_the_frame_it_started() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Slow { amount: 0.3, duration: 5.0 }, 0);
+        effects.apply(StatusEffect::Freeze { duration: 1.0 }, 3);
+        effects.apply(StatusEffect::Freeze { duration: 0.5 }, 7);
+
+        assert_eq!(effects.speed_multiplier(), 0.0);
+        assert_eq!(effects.frozen_frame, 3);
+
+        effects.update(1.0);
+        assert!(!effects.is_frozen());
+        assert!((effects.speed_multiplier() - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn damage_lands_in_ticks() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Burn { dps: 4.0, duration: 2.0 }, 0);
+
+        assert_eq!(effects.update(0.25), 0.0);
+        assert_eq!(effects.update(0.25), 2.0);
+        assert_eq!(effects.update(0.25), 0.0);
+    }
+
+    #[test]
+    fn burn_durations_add_up() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Burn { dps: 1.0, duration: 1.0 }, 0);
+        effects.apply(StatusEffect::Burn { dps: 1.0, duration: 1.0 }, 0);
+
+        effects.update(1.5);
+        assert!(effects.is_burning());
+        effects.update(0.5);
+        assert!(!effects.is_burning());
+    }
+
+    #[test]
+    fn poison_stacks_are_capped() {
+        let mut effects = StatusEffects::default();
+        for _ in 0..MAX_POISON_STACKS + 3 {
+            effects.apply(StatusEffect::Poison { dps: 1.0, duration: 3.0 }, 0);
+        }
+
+        // One tick of every stack at 1 damage per second
+        assert_eq!(effects.update(TICK_INTERVAL), MAX_POISON_STACKS as f32 * TICK_INTERVAL);
+
+        effects.update(3.0);
+        assert!(!effects.is_poisoned());
+        assert_eq!(effects.update(TICK_INTERVAL), 0.0);
+    }
+
+    #[test]
+    fn knockback_eases_out_over_its_ticks() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Knockback { impulse: vec2(6.0, 0.0) }, 0);
+
+        let steps: Vec<f32> = std::iter::from_fn(|| effects.take_knockback()).map(|step| step.x).collect();
+        assert_eq!(steps, vec![6.0, 5.0, 4.0, 3.0, 2.0, 1.0]);
+        assert!(!effects.is_knocked_back());
+    }
+}
@@ -1,3 +1,4 @@
 mod screen;
+mod waves;
 
 pub use screen::Game;
\ No newline at end of file
@@ -1,55 +1,122 @@
 use macroquad::prelude::*;
+use std::rc::Rc;
+use std::cell::RefCell;
 
-use crate::player::Player;
-use crate::enemies::{EnemySystem, PositionOverlap};
-use crate::constants::{WORLD_WIDTH, WORLD_HEIGHT, virtual_height, virtual_width};
-use crate::components::joystick::Joystick;
-use crate::components::layout::{is_mobile};
-use crate::strategies::{BoidsMovement, AABBCollision};
+use crate::player::{Character, Player};
+use crate::enemies::{Boss, EnemySystem, PositionOverlap};
+use crate::combat::TargetLayer;
+use crate::weapons::ShotSystem;
+use crate::pickups::{PickupKind, Pickups};
+use crate::particles::{self, Emitter, ParticleSystem};
+use crate::damage_numbers::DamageNumbers;
+use crate::materials::Materials;
+use crate::post_processing::PostProcessing;
+use crate::lighting::{Light, Lighting};
+use crate::game::waves::{WaveDirector, WaveEvent};
+use crate::constants::{PARTICLE_BUDGET, virtual_height, virtual_width};
+use crate::components::joystick::{Joystick, ScreenSide};
+use crate::components::layout::{ViewportWatcher, is_mobile};
+use crate::strategies::{FlowFieldMovement, KeepDistanceMovement, AABBCollision, CircleCollision};
+use crate::projectiles::ProjectilePool;
+use crate::input::{Action, InputMap};
+use crate::hud;
+use crate::stats::{Stat, Stats};
+use crate::settings::Preferences;
+use crate::world::{World, load_world};
 
 pub struct Game {
     player: Player,
     enemies: EnemySystem,
+    boss: Option<Boss>,
+    shots: ShotSystem,
+    projectiles: ProjectilePool,
+    pickups: Pickups,
+    particles: ParticleSystem,
+    dash_trail: Emitter,
+    damage_numbers: DamageNumbers,
+    materials: Materials,
+    post_processing: PostProcessing,
+    lighting: Lighting,
+    waves: WaveDirector,
+    notice: hud::Notice,
     camera: Camera2D,
-    pub joystick: Option<Joystick>,
+    move_stick: Joystick,
+    aim_stick: Joystick,
+    input: Rc<RefCell<InputMap>>,
+    viewport: ViewportWatcher,
+    preferences: Rc<RefCell<Preferences>>,
+    /// Preferences revision the sticks were built with.
+    preferences_revision: u32,
+    world: World,
 }
 
 impl Game {
-    pub async fn new(joystick: Option<Joystick>) -> Self {
-
+    pub async fn new(input: Rc<RefCell<InputMap>>, preferences: &Rc<RefCell<Preferences>>, character: &Character) -> Self {
         let camera = Camera2D {
             zoom: vec2(2.0 / virtual_width(), -2.0 / virtual_height()),
             target: vec2(virtual_width() / 2.0, virtual_height() / 2.0),
             ..Default::default()
         };
 
-        let movement_strategy = Box::new(BoidsMovement {
-            visual_range: 32.0,
-            separation_dist: 40.0,
-            max_speed: 3.0,
-            player_weight: 0.8,
-            player_distance: 2000.0, 
-            noise_strength: 0.05,
-            separation_weight: 3.2,
-            alignment_weight: 1.5,
-            cohesion_weight: 0.3,
+        let world = if preferences.borrow().infinite_world {
+            // The clock makes every run a different world
+            let seed = (miniquad::date::now() * 1000.0) as u64;
+            World::infinite(seed).await
+        } else {
+            load_world().await
+        };
+
+        let movement_strategy = Box::new(FlowFieldMovement::new(3.0));
+
+        let ranged_strategy = Box::new(KeepDistanceMovement {
+            preferred_distance: 450.0,
+            tolerance: 80.0,
+            max_speed: 2.5,
         });
 
         let collision_strategy = Box::new(AABBCollision {});
 
-        let enemies = EnemySystem::new(
+        let mut enemies = EnemySystem::new(
             100, 
+            world.area(),
             movement_strategy, 
+            ranged_strategy,
             collision_strategy,
-        ).await;
+        ).await
+        .relocation_radius(1800.0)
+        .spawn_points(world.enemy_spawns.clone());
+        if world.bounds.is_none() {
+            enemies = enemies.unbounded();
+        }
 
-        let player = Player::new(100.0, 100.0).await;
+        let player = Player::new(world.player_spawn.x, world.player_spawn.y, character).await;
+
+        let move_stick = new_stick(ScreenSide::Left, &preferences.borrow());
+        let aim_stick = new_stick(ScreenSide::Right, &preferences.borrow());
 
         Game {
             player,
             enemies,
+            boss: None,
+            shots: ShotSystem::new(),
+            projectiles: ProjectilePool::new(512, Box::new(CircleCollision {})),
+            pickups: Pickups::new(),
+            particles: ParticleSystem::new(PARTICLE_BUDGET),
+            dash_trail: Emitter::new(particles::DASH_TRAIL, 240.0),
+            damage_numbers: DamageNumbers::new(256),
+            materials: Materials::load(),
+            post_processing: PostProcessing::new(Rc::clone(preferences)).await,
+            lighting: Lighting::new(),
+            waves: WaveDirector::new(),
+            notice: hud::Notice::new(),
             camera,
-            joystick
+            move_stick,
+            aim_stick,
+            input,
+            viewport: ViewportWatcher::new(),
+            preferences: Rc::clone(preferences),
+            preferences_revision: preferences.borrow().revision(),
+            world,
         }
     }
 
@@ -60,34 +127,167 @@ impl Game {
     pub fn update(&mut self) {
         clear_background(BLACK);
 
+        if self.viewport.changed() {
+            for stick in [&mut self.move_stick, &mut self.aim_stick] {
+                stick.set_base_position(Joystick::default_base_position(stick.radius, stick.side));
+            }
+        }
+
+        // Joystick mode and deadzone can change in the settings while a run is going
+        let preferences = self.preferences.borrow();
+        if preferences.revision() != self.preferences_revision {
+            self.move_stick = new_stick(ScreenSide::Left, &preferences);
+            self.aim_stick = new_stick(ScreenSide::Right, &preferences);
+            self.preferences_revision = preferences.revision();
+        }
+        drop(preferences);
+
         self.camera.zoom = calculate_camera_zoom();
-        self.camera.target = clamp_camera_target(self.player.position());
-        set_camera(&self.camera);
+        if self.world.update(self.player.center()) {
+            // Shots remember props by index, which just changed
+            self.shots.forget_hits(TargetLayer::Props);
+        }
+        self.enemies.set_area(self.world.area());
 
-        draw_rectangle(0.0, 0.0, WORLD_WIDTH, WORLD_HEIGHT, Color::from_rgba(30, 30, 30, 255));
+        // Worlds without edges let the camera follow the player anywhere
+        self.camera.target = match self.world.bounds {
+            Some(bounds) => clamp_camera_target(self.player.position(), bounds),
+            None => self.player.position(),
+        };
+        let view_half_size = vec2(1.0 / self.camera.zoom.x, 1.0 / self.camera.zoom.y).abs();
+        let view = Rect::new(
+            self.camera.target.x - view_half_size.x,
+            self.camera.target.y - view_half_size.y,
+            view_half_size.x * 2.0,
+            view_half_size.y * 2.0,
+        );
+        // The sticks always exist but only drive the player while the layout is mobile
+        self.move_stick.update();
+        self.aim_stick.update();
+        let (move_value, aim_value) = if is_mobile() {
+            (Some(self.move_stick.value()), Some(self.aim_stick.value()))
+        } else {
+            (None, None)
+        };
 
-        let joystick_dir = self.joystick.as_mut().map(|joy| {
-            joy.update();
-            joy.direction()
-        });
+        // Tapping the aim stick without dragging it dashes
+        let input = self.input.borrow();
+        let dash_requested = input.is_pressed(Action::Dash) || self.aim_stick.was_tapped();
+        self.player.aim_direction = input.aim_axis(aim_value);
+        self.player.update(input.move_axis(move_value), dash_requested, &self.world);
+        drop(input);
 
-        self.player.update_with_direction(joystick_dir);
-        self.player.update();
+        let dt = get_frame_time();
+        if self.player.dash.is_active() {
+            let direction = self.player.dash.direction();
+            let behind = -vec2(direction.x, -direction.y);
+            self.dash_trail.update(&mut self.particles, self.player.center(), behind, dt);
+        }
 
-        self.enemies.update(self.player.position(), &mut self.player);
+        self.update_waves();
+        self.update_weapons();
 
-        self.enemies.draw(self.player.position(), PositionOverlap::Behind);
-        self.player.draw();
-        self.enemies.draw(self.player.position(),PositionOverlap::InFront);
+        self.enemies.relocate_far(self.player.center(), self.player.travel_direction(), view_half_size.length());
 
-        set_default_camera();
+        let elite_deaths = self.enemies.update(self.player.position(), &self.world, &mut self.player, &mut self.projectiles);
+        for (position, affix) in elite_deaths {
+            // Elites always drop something, luck turns more of it into treasure
+            let treasure_chance = affix.treasure_chance * self.player.stats.get(Stat::Luck);
+            let kind = if rand::gen_range(0.0, 1.0) < treasure_chance {
+                PickupKind::Treasure
+            } else {
+                PickupKind::Heal(30.0)
+            };
+            self.pickups.spawn(position, kind);
+        }
+        if let Some(boss) = self.boss.as_mut() {
+            boss.update(&mut self.player, &mut self.enemies, &mut self.projectiles);
+        }
+        self.projectiles.update(&mut self.player);
+        for stat in self.pickups.update(&mut self.player) {
+            let value = stat.format(self.player.stats.get(stat));
+            self.notice.show(format!("Treasure: {} is now {}", stat.label(), value));
+        }
+        self.notice.update(dt);
+        self.pickups.emit_sparkles(&mut self.particles, dt);
 
-        if let Some(joystick) = &self.joystick {
-            joystick.draw();
+        for center in self.enemies.take_deaths() {
+            self.particles.burst(&particles::DEATH_BURST, center, Vec2::ZERO, 16);
         }
+        self.particles.update(dt);
+
+        if let Some(amount) = self.player.take_healed() {
+            if self.preferences.borrow().damage_numbers {
+                let above = self.player.position() + vec2(self.player.size / 2.0, self.player.size);
+                self.damage_numbers.heal(above, amount);
+            }
+        }
+        self.damage_numbers.update(dt);
+
+        self.lighting.update(dt);
+        self.add_lights();
+        self.lighting.render(&self.camera);
+
+        self.post_processing.begin(&self.camera, self.player.hit_intensity());
+        self.world.draw_ground(view);
+
+        if let Some(boss) = &self.boss {
+            boss.draw_telegraphs();
+        }
+        self.enemies.draw_explosions();
+        self.pickups.draw();
+
+        self.world.obstacles.draw(self.player.position(), PositionOverlap::Behind);
+        self.world.destructibles.draw(self.player.position(), PositionOverlap::Behind);
+        let sight = self.lighting.sight_radius();
+        self.enemies.draw(self.player.position(), PositionOverlap::Behind, sight, &self.materials);
+        self.player.draw(&self.materials);
+        self.enemies.draw(self.player.position(), PositionOverlap::InFront, sight, &self.materials);
+        self.world.destructibles.draw(self.player.position(), PositionOverlap::InFront);
+        self.world.obstacles.draw(self.player.position(), PositionOverlap::InFront);
+
+        if let Some(boss) = &self.boss {
+            boss.draw();
+        }
+        self.particles.draw();
+        self.shots.draw();
+        self.projectiles.draw();
+
+        self.lighting.apply(view);
+
+        self.post_processing.finish();
+
+        self.materials.draw_vignette(self.player.hit_intensity());
+
+        if self.preferences.borrow().damage_numbers {
+            self.damage_numbers.draw(&self.camera);
+        }
+
+        self.move_stick.draw();
+        self.aim_stick.draw();
+
+        hud::draw_dash_indicator(&self.player, vec2(60.0, 100.0), 40.0);
+        if let Some(boss) = &self.boss {
+            hud::draw_boss_bar(boss);
+        }
+        self.notice.draw();
+
+        let controls_hint = if self.input.borrow().gamepads().is_connected() {
+            "Left stick or D-pad to move"
+        } else {
+            "WASD or Arrows to move"
+        };
 
         draw_text(
-            &format!("WASD or Arrows to move | FPS: {} | enemies {}", get_fps(), self.enemies.positions.len()),
+            &format!(
+                "{} | FPS: {} | enemies {} | wave {} ({:.0}s) | gold {}",
+                controls_hint,
+                get_fps(),
+                self.enemies.live_count(),
+                self.waves.wave(),
+                self.waves.time_left(),
+                self.player.gold,
+            ),
             20.0,
             30.0,
             30.0,
@@ -95,20 +295,143 @@ impl Game {
         );
     }
 
+    /// Lights for this frame: the player carries one, every projectile glows and so do elites.
+    fn add_lights(&mut self) {
+        self.lighting.add(Light {
+            position: self.player.center(),
+            radius: 420.0,
+            color: Color::new(1.0, 0.9, 0.7, 1.0),
+        });
+
+        for projectile in self.projectiles.iter() {
+            self.lighting.add(Light {
+                position: projectile.position,
+                radius: projectile.radius * 8.0,
+                color: projectile.color,
+            });
+        }
+
+        for shot in self.shots.iter() {
+            self.lighting.add(Light {
+                position: shot.position,
+                radius: shot.radius * 4.0,
+                color: Color { a: 0.6, ..shot.color },
+            });
+        }
+
+        for (center, color) in self.enemies.elite_glows() {
+            self.lighting.add(Light { position: center, radius: 180.0, color });
+        }
+    }
+
+    /// Refills the horde as waves start, and brings a boss on milestone waves.
+    fn update_waves(&mut self) {
+        let (wave, boss_wave) = match self.waves.update(get_frame_time()) {
+            Some(WaveEvent::Started(wave)) => (wave, false),
+            Some(WaveEvent::Boss(wave)) => (wave, true),
+            None => return,
+        };
+
+        self.enemies.respawn(10 * wave as usize);
+
+        if boss_wave && self.boss.is_none() {
+            // Just outside the view, so the boss walks in instead of popping up on the player
+            let offset = Vec2::from_angle(rand::gen_range(0.0, std::f32::consts::TAU)) * 700.0;
+            let position = self.world.clamp(self.player.center() + offset, Vec2::ZERO);
+            self.boss = Some(Boss::new(position, wave, self.enemies.texture().cloned(), self.world.bounds));
+        }
+    }
+
+    /// Fires the player's weapon and applies the damage of every shot that landed.
+    fn update_weapons(&mut self) {
+        let origin = self.player.center();
+        let range = self.player.weapon.range;
+
+        // Manual aim wins, otherwise the weapon picks the closest target in range
+        let nearest_enemy = self.enemies.nearest(origin, range);
+        let boss_center = self.boss.as_ref()
+            .map(|boss| boss.center())
+            .filter(|center| center.distance(origin) <= range);
+        let target = match (nearest_enemy, boss_center) {
+            (Some(enemy), Some(boss)) if boss.distance(origin) < enemy.distance(origin) => Some(boss),
+            (Some(enemy), _) => Some(enemy),
+            (None, boss) => boss,
+        };
+
+        let direction = self.player.aim_direction
+            .map(|aim| vec2(aim.x, -aim.y))
+            .or(target.map(|target| target - origin));
+        self.player.weapon.update(origin, direction, &self.player.stats, &mut self.shots);
+
+        self.shots.update();
+        let mut hits = self.shots.resolve(TargetLayer::Enemies, &mut self.enemies);
+        hits.extend(self.shots.resolve(TargetLayer::Props, &mut self.world.destructibles));
+
+        let luck = self.player.stats.get(Stat::Luck);
+        for (center, kind) in self.world.destructibles.take_broken() {
+            self.particles.burst(&particles::DEBRIS, center, Vec2::ZERO, 12);
+            if let Some(drop) = kind.roll_drop(luck) {
+                self.pickups.spawn(center, drop);
+            }
+        }
+
+        if let Some(boss) = self.boss.as_mut() {
+            hits.extend(self.shots.resolve(TargetLayer::Boss, boss));
+
+            if boss.is_dead() {
+                self.particles.burst(&particles::DEATH_BURST, boss.center(), Vec2::ZERO, 60);
+                self.pickups.spawn(boss.center(), PickupKind::Treasure);
+                self.boss = None;
+            }
+        }
+
+        // Sparks fly on past the target, away from the player
+        let show_numbers = self.preferences.borrow().damage_numbers;
+        for hit in hits {
+            self.particles.burst(&particles::HIT_SPARKS, hit.position, hit.position - origin, 4);
+            if show_numbers {
+                // Over the target's head, the boss may already be gone if this hit killed it
+                let above = match hit.layer {
+                    TargetLayer::Enemies => self.enemies.positions[hit.index] + self.enemies.sizes[hit.index] * vec2(0.5, 1.0),
+                    TargetLayer::Boss => self.boss.as_ref().map_or(hit.position, |boss| boss.position + boss.size * vec2(0.5, 1.0)),
+                    TargetLayer::Props => hit.position,
+                };
+                self.damage_numbers.hit(&hit, above);
+            }
+        }
+    }
+
+    pub fn player_stats(&self) -> &Stats {
+        &self.player.stats
+    }
+
+    /// Seed of the infinite world, `None` on a fixed map.
+    pub fn world_seed(&self) -> Option<u64> {
+        self.world.seed
+    }
+
     pub fn is_game_over(&self) -> bool {
         self.player.health <= 0.0
     }
 }
 
+fn new_stick(side: ScreenSide, preferences: &Preferences) -> Joystick {
+    let radius = 200.0;
+
+    Joystick::new(Joystick::default_base_position(radius, side), radius)
+        .side(side)
+        .mode(preferences.joystick_mode)
+        .deadzone(preferences.joystick_deadzone)
+}
+
 #[inline]
-fn clamp_camera_target(player_position: Vec2) -> Vec2 {
-    let half_screen_width = virtual_width() / 2.0;
-    let half_screen_height = virtual_height() / 2.0;
+fn clamp_camera_target(player_position: Vec2, bounds: Rect) -> Vec2 {
+    let half_screen = vec2(virtual_width(), virtual_height()) / 2.0;
 
-    Vec2::new(
-        player_position.x.clamp(half_screen_width, WORLD_WIDTH - half_screen_width),
-        player_position.y.clamp(half_screen_height, WORLD_HEIGHT - half_screen_height),
-    )
+    // Maps smaller than the screen stick to their bottom-left corner
+    let min = bounds.point() + half_screen;
+    let max = (bounds.point() + bounds.size() - half_screen).max(min);
+    player_position.clamp(min, max)
 }
 
 #[inline]
@@ -116,7 +439,7 @@ fn calculate_camera_zoom() -> Vec2 {
     if is_mobile() { 
         return vec2(2.0 / virtual_width(), -2.0 / virtual_height())
     }
-    return vec2(
+    vec2(
         2.0 / virtual_width() * (virtual_width() / screen_width()),
         -2.0 / virtual_height() * (virtual_height() / screen_height())
     )
@@ -0,0 +1,84 @@
+/// Seconds each wave lasts before the next one starts.
+const WAVE_DURATION: f32 = 30.0;
+/// Every this many waves a boss shows up.
+const BOSS_EVERY: u32 = 5;
+
+pub enum WaveEvent {
+    /// A new wave began, refill the horde.
+    Started(u32),
+    /// A milestone wave began, spawn a boss along with the horde.
+    Boss(u32),
+}
+
+/// Keeps time for the run and announces waves.
+pub struct WaveDirector {
+    wave: u32,
+    timer: f32,
+}
+
+impl WaveDirector {
+    pub fn new() -> Self {
+        Self {
+            wave: 1,
+            timer: 0.0,
+        }
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Seconds until the next wave starts.
+    pub fn time_left(&self) -> f32 {
+        (WAVE_DURATION - self.timer).max(0.0)
+    }
+
+    pub fn update(&mut self, dt: f32) -> Option<WaveEvent> {
+        self.timer += dt;
+
+        if self.timer < WAVE_DURATION {
+            return None;
+        }
+
+        self.timer = 0.0;
+        self.wave += 1;
+
+        if self.wave.is_multiple_of(BOSS_EVERY) {
+            Some(WaveEvent::Boss(self.wave))
+        } else {
+            Some(WaveEvent::Started(self.wave))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waves_start_every_wave_duration() {
+        let mut waves = WaveDirector::new();
+
+        assert!(waves.update(WAVE_DURATION - 1.0).is_none());
+        assert_eq!(waves.time_left(), 1.0);
+        assert!(matches!(waves.update(1.0), Some(WaveEvent::Started(2))));
+        assert_eq!(waves.wave(), 2);
+        assert_eq!(waves.time_left(), WAVE_DURATION);
+    }
+
+    #[test]
+    fn every_fifth_wave_brings_a_boss() {
+        let mut waves = WaveDirector::new();
+        let events: Vec<WaveEvent> = (0..10).filter_map(|_| waves.update(WAVE_DURATION)).collect();
+
+        let bosses: Vec<u32> = events
+            .iter()
+            .filter_map(|event| match event {
+                WaveEvent::Boss(wave) => Some(*wave),
+                WaveEvent::Started(_) => None,
+            })
+            .collect();
+        assert_eq!(events.len(), 10);
+        assert_eq!(bosses, vec![5, 10]);
+    }
+}
@@ -0,0 +1,98 @@
+use macroquad::prelude::*;
+use crate::player::Player;
+use crate::enemies::Boss;
+
+/// Seconds a notice stays up, the last of it fading out.
+const NOTICE_DURATION: f32 = 3.0;
+
+/// Dash cooldown as a ring that fills up, drawn in screen space.
+pub fn draw_dash_indicator(player: &Player, center: Vec2, radius: f32) {
+    let readiness = player.dash.readiness();
+    let ready = readiness >= 1.0;
+
+    draw_circle(center.x, center.y, radius, Color::from_rgba(0, 0, 0, 120));
+    draw_arc(
+        center.x,
+        center.y,
+        32,
+        radius,
+        -90.0,
+        radius * 0.25,
+        360.0 * readiness,
+        if ready { Color::from_rgba(120, 200, 255, 255) } else { GRAY },
+    );
+
+    let font_size = radius * 0.6;
+    let label = "DASH";
+    let text_dim = measure_text(label, None, font_size as u16, 1.0);
+    draw_text(
+        label,
+        center.x - text_dim.width / 2.0,
+        center.y + text_dim.offset_y / 2.0,
+        font_size,
+        if ready { WHITE } else { GRAY },
+    );
+}
+
+/// Large boss health bar across the top of the screen, with ticks at the phase thresholds.
+pub fn draw_boss_bar(boss: &Boss) {
+    let width = screen_width() * 0.6;
+    let height = 22.0;
+    let x = (screen_width() - width) / 2.0;
+    let y = 70.0;
+    let ratio = (boss.health / boss.max_health).clamp(0.0, 1.0);
+
+    draw_rectangle(x - 3.0, y - 3.0, width + 6.0, height + 6.0, Color::from_rgba(0, 0, 0, 180));
+    draw_rectangle(x, y, width, height, Color::from_rgba(60, 10, 10, 255));
+    draw_rectangle(x, y, width * ratio, height, Color::from_rgba(200, 30, 60, 255));
+
+    for threshold in boss.phase_thresholds() {
+        let tick_x = x + width * threshold;
+        draw_line(tick_x, y, tick_x, y + height, 2.0, WHITE);
+    }
+
+    let label = format!("{} - Phase {}", boss.name, boss.phase() + 1);
+    let font_size = 26.0;
+    let text_dim = measure_text(&label, None, font_size as u16, 1.0);
+    draw_text(&label, x + (width - text_dim.width) / 2.0, y - 8.0, font_size, WHITE);
+}
+
+/// Short message under the top bar, such as what a treasure chest granted.
+pub struct Notice {
+    text: String,
+    remaining: f32,
+}
+
+impl Notice {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            remaining: 0.0,
+        }
+    }
+
+    /// Replaces whatever was showing.
+    pub fn show(&mut self, text: String) {
+        self.text = text;
+        self.remaining = NOTICE_DURATION;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.remaining = (self.remaining - dt).max(0.0);
+    }
+
+    pub fn draw(&self) {
+        if self.remaining <= 0.0 {
+            return;
+        }
+
+        let font_size = 34.0;
+        let alpha = self.remaining.min(1.0);
+        let text_dim = measure_text(&self.text, None, font_size as u16, 1.0);
+        let x = (screen_width() - text_dim.width) / 2.0;
+        let y = 130.0;
+
+        draw_text(&self.text, x + 2.0, y + 2.0, font_size, Color::new(0.0, 0.0, 0.0, alpha * 0.6));
+        draw_text(&self.text, x, y, font_size, Color { a: alpha, ..GOLD });
+    }
+}
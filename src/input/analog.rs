@@ -0,0 +1,55 @@
+use macroquad::prelude::*;
+
+/// Shapes analog stick magnitudes once the deadzone has been removed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResponseCurve {
+    Linear,
+    Quadratic,
+    Cubic,
+}
+
+impl ResponseCurve {
+    pub const ALL: [ResponseCurve; 3] = [
+        ResponseCurve::Linear,
+        ResponseCurve::Quadratic,
+        ResponseCurve::Cubic,
+    ];
+
+    pub fn apply(&self, magnitude: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => magnitude,
+            ResponseCurve::Quadratic => magnitude * magnitude,
+            ResponseCurve::Cubic => magnitude * magnitude * magnitude,
+        }
+    }
+
+    /// Applies the curve to the length of `value`, keeping its direction.
+    pub fn shape(&self, value: Vec2) -> Vec2 {
+        let magnitude = value.length();
+        if magnitude <= 0.0 {
+            return Vec2::ZERO;
+        }
+
+        value / magnitude * self.apply(magnitude.min(1.0))
+    }
+
+    pub fn next(&self) -> ResponseCurve {
+        let index = ResponseCurve::ALL.iter().position(|curve| curve == self).unwrap_or(0);
+        ResponseCurve::ALL[(index + 1) % ResponseCurve::ALL.len()]
+    }
+
+    pub fn from_name(name: &str) -> Option<ResponseCurve> {
+        ResponseCurve::ALL.into_iter().find(|curve| format!("{:?}", curve) == name)
+    }
+}
+
+/// Zeroes `value` inside the deadzone and rescales the rest of the range back to 0..1.
+pub fn remove_deadzone(value: Vec2, deadzone: f32) -> Vec2 {
+    let magnitude = value.length();
+    if magnitude <= deadzone {
+        return Vec2::ZERO;
+    }
+
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+    value / magnitude * scaled
+}
@@ -0,0 +1,100 @@
+use macroquad::prelude::*;
+
+use super::gamepad::{GamepadButton, Gamepads};
+
+/// A physical input that can be bound to an action.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+/// Keys that can be captured by the rebinding screen and stored in the bindings file.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+    KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Enter, KeyCode::KpEnter, KeyCode::Escape, KeyCode::Back,
+    KeyCode::Tab, KeyCode::Backspace, KeyCode::Insert, KeyCode::Delete,
+    KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::LeftAlt, KeyCode::RightAlt,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon,
+    KeyCode::Apostrophe, KeyCode::Minus, KeyCode::Equal,
+    KeyCode::LeftBracket, KeyCode::RightBracket, KeyCode::Backslash, KeyCode::GraveAccent,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+];
+
+const BINDABLE_MOUSE_BUTTONS: &[MouseButton] = &[
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+];
+
+impl Binding {
+    pub fn is_down(&self, gamepads: &Gamepads) -> bool {
+        match self {
+            Binding::Key(key) => is_key_down(*key),
+            Binding::Mouse(button) => is_mouse_button_down(*button),
+            Binding::Gamepad(button) => gamepads.is_down(*button),
+        }
+    }
+
+    pub fn is_pressed(&self, gamepads: &Gamepads) -> bool {
+        match self {
+            Binding::Key(key) => is_key_pressed(*key),
+            Binding::Mouse(button) => is_mouse_button_pressed(*button),
+            Binding::Gamepad(button) => gamepads.is_pressed(*button),
+        }
+    }
+
+    pub fn is_bindable_key(key: KeyCode) -> bool {
+        BINDABLE_KEYS.contains(&key)
+    }
+
+    /// Human readable name shown in the settings screen.
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            Binding::Gamepad(button) => format!("Pad {:?}", button),
+        }
+    }
+
+    /// Serialized form used in the bindings file, e.g. `Key:W`, `Mouse:Right` or `Gamepad:South`.
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("Key:{:?}", key),
+            Binding::Mouse(button) => format!("Mouse:{:?}", button),
+            Binding::Gamepad(button) => format!("Gamepad:{:?}", button),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Binding> {
+        let (kind, value) = name.split_once(':')?;
+
+        match kind {
+            "Key" => BINDABLE_KEYS
+                .iter()
+                .find(|key| format!("{:?}", key) == value)
+                .map(|key| Binding::Key(*key)),
+            "Mouse" => BINDABLE_MOUSE_BUTTONS
+                .iter()
+                .find(|button| format!("{:?}", button) == value)
+                .map(|button| Binding::Mouse(*button)),
+            "Gamepad" => GamepadButton::ALL
+                .into_iter()
+                .find(|button| format!("{:?}", button) == value)
+                .map(Binding::Gamepad),
+            _ => None,
+        }
+    }
+}
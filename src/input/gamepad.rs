@@ -0,0 +1,294 @@
+use macroquad::prelude::*;
+use std::collections::HashSet;
+
+use super::analog::remove_deadzone;
+
+/// Buttons of a standard gamepad layout, named after their position.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    Start,
+    Select,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 12] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::Start,
+        GamepadButton::Select,
+        GamepadButton::LeftShoulder,
+        GamepadButton::RightShoulder,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+    ];
+}
+
+/// Raw state of one gamepad, sticks are in screen space (y grows downwards).
+#[derive(Clone, Default)]
+pub struct GamepadState {
+    pub left_stick: Vec2,
+    pub right_stick: Vec2,
+    pub buttons: HashSet<GamepadButton>,
+}
+
+/// Only the gilrs and browser backends and the tests report events, Linux builds without
+/// the `gamepad` feature never see one.
+#[cfg_attr(not(any(feature = "gamepad", target_os = "windows", target_os = "macos", target_arch = "wasm32")), allow(dead_code))]
+pub enum GamepadEvent {
+    Connected { id: usize, name: String },
+    Disconnected { id: usize },
+}
+
+/// Source of gamepad data, so the device layer can be swapped per platform or in tests.
+pub trait GamepadBackend {
+    /// Pumps the device layer, reporting pads that were plugged in or removed.
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+
+    fn state(&self, id: usize) -> Option<GamepadState>;
+}
+
+/// Tracks the active gamepad and its state across frames.
+pub struct Gamepads {
+    backend: Box<dyn GamepadBackend>,
+    connected: Vec<usize>,
+    active: Option<usize>,
+    current: GamepadState,
+    previous: GamepadState,
+    pub deadzone: f32,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Self::with_backend(default_backend())
+    }
+
+    pub fn with_backend(backend: Box<dyn GamepadBackend>) -> Self {
+        Self {
+            backend,
+            connected: Vec::new(),
+            active: None,
+            current: GamepadState::default(),
+            previous: GamepadState::default(),
+            deadzone: 0.2,
+        }
+    }
+
+    pub fn update(&mut self) {
+        for event in self.backend.poll() {
+            match event {
+                GamepadEvent::Connected { id, name } => {
+                    println!("Gamepad connected: {}", name);
+                    if !self.connected.contains(&id) {
+                        self.connected.push(id);
+                    }
+                    // The most recently plugged pad takes over
+                    self.active = Some(id);
+                }
+                GamepadEvent::Disconnected { id } => {
+                    println!("Gamepad disconnected");
+                    self.connected.retain(|connected| *connected != id);
+                    if self.active == Some(id) {
+                        self.active = self.connected.last().copied();
+                    }
+                }
+            }
+        }
+
+        self.previous = std::mem::take(&mut self.current);
+        self.current = self.active
+            .and_then(|id| self.backend.state(id))
+            .unwrap_or_default();
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.current.buttons.contains(&button)
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.is_down(button) && !self.previous.buttons.contains(&button)
+    }
+
+    /// First button pressed this frame, used when capturing a new binding.
+    pub fn last_pressed(&self) -> Option<GamepadButton> {
+        GamepadButton::ALL.into_iter().find(|button| self.is_pressed(*button))
+    }
+
+    /// Left stick with the deadzone removed, its length goes from 0 to 1.
+    pub fn left_stick(&self) -> Vec2 {
+        remove_deadzone(self.current.left_stick, self.deadzone)
+    }
+
+    /// Right stick with the deadzone removed, its length goes from 0 to 1.
+    pub fn right_stick(&self) -> Vec2 {
+        remove_deadzone(self.current.right_stick, self.deadzone)
+    }
+}
+
+/// gilrs on native builds, always on Windows and macOS and behind the `gamepad` feature
+/// on Linux, where it needs `libudev`.
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "gamepad", target_os = "windows", target_os = "macos")))]
+fn default_backend() -> Box<dyn GamepadBackend> {
+    match super::gilrs_backend::GilrsBackend::new() {
+        Some(backend) => Box::new(backend),
+        None => Box::new(NoGamepadBackend),
+    }
+}
+
+/// The browser's Gamepad API on the web.
+#[cfg(target_arch = "wasm32")]
+fn default_backend() -> Box<dyn GamepadBackend> {
+    Box::new(super::web_gamepad::WebGamepadBackend::new())
+}
+
+#[cfg(not(any(feature = "gamepad", target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+fn default_backend() -> Box<dyn GamepadBackend> {
+    Box::new(NoGamepadBackend)
+}
+
+/// Used when the platform has no gamepad support.
+pub struct NoGamepadBackend;
+
+impl GamepadBackend for NoGamepadBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        Vec::new()
+    }
+
+    fn state(&self, _id: usize) -> Option<GamepadState> {
+        None
+    }
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct SyntheticPads {
+    events: Vec<GamepadEvent>,
+    states: std::collections::HashMap<usize, GamepadState>,
+}
+
+/// Backend driven by hand, for scripting gamepad input without a device.
+///
+/// Clones share the same pads, so a handle can be kept after boxing one into `Gamepads`.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct SyntheticBackend {
+    pads: std::rc::Rc<std::cell::RefCell<SyntheticPads>>,
+}
+
+#[cfg(test)]
+impl SyntheticBackend {
+    pub fn connect(&self, id: usize) {
+        let mut pads = self.pads.borrow_mut();
+        pads.states.insert(id, GamepadState::default());
+        pads.events.push(GamepadEvent::Connected { id, name: format!("Synthetic gamepad {}", id) });
+    }
+
+    pub fn disconnect(&self, id: usize) {
+        let mut pads = self.pads.borrow_mut();
+        pads.states.remove(&id);
+        pads.events.push(GamepadEvent::Disconnected { id });
+    }
+
+    pub fn set_state(&self, id: usize, state: GamepadState) {
+        self.pads.borrow_mut().states.insert(id, state);
+    }
+}
+
+#[cfg(test)]
+impl GamepadBackend for SyntheticBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        std::mem::take(&mut self.pads.borrow_mut().events)
+    }
+
+    fn state(&self, id: usize) -> Option<GamepadState> {
+        self.pads.borrow().states.get(&id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Binding;
+
+    fn pressing(buttons: &[GamepadButton]) -> GamepadState {
+        GamepadState {
+            buttons: buttons.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bound_buttons_are_pressed_for_one_frame() {
+        let backend = SyntheticBackend::default();
+        let mut gamepads = Gamepads::with_backend(Box::new(backend.clone()));
+        let confirm = Binding::Gamepad(GamepadButton::South);
+
+        backend.connect(0);
+        backend.set_state(0, pressing(&[GamepadButton::South]));
+        gamepads.update();
+        assert!(confirm.is_down(&gamepads));
+        assert!(confirm.is_pressed(&gamepads));
+        assert_eq!(gamepads.last_pressed(), Some(GamepadButton::South));
+
+        gamepads.update();
+        assert!(confirm.is_down(&gamepads));
+        assert!(!confirm.is_pressed(&gamepads));
+        assert!(!Binding::Gamepad(GamepadButton::East).is_down(&gamepads));
+    }
+
+    #[test]
+    fn sticks_ignore_the_deadzone_and_rescale_the_rest() {
+        let backend = SyntheticBackend::default();
+        let mut gamepads = Gamepads::with_backend(Box::new(backend.clone()));
+        backend.connect(0);
+
+        backend.set_state(0, GamepadState { left_stick: vec2(0.15, 0.0), right_stick: vec2(0.0, -1.0), ..Default::default() });
+        gamepads.update();
+        assert_eq!(gamepads.left_stick(), Vec2::ZERO);
+        assert_eq!(gamepads.right_stick(), vec2(0.0, -1.0));
+
+        backend.set_state(0, GamepadState { left_stick: vec2(0.6, 0.0), ..Default::default() });
+        gamepads.update();
+        assert!((gamepads.left_stick().x - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn unplugging_the_active_pad_falls_back_to_the_previous_one() {
+        let backend = SyntheticBackend::default();
+        let mut gamepads = Gamepads::with_backend(Box::new(backend.clone()));
+
+        backend.connect(0);
+        backend.connect(1);
+        backend.set_state(0, pressing(&[GamepadButton::North]));
+        gamepads.update();
+        // The pad plugged in last is the one being read
+        assert!(!gamepads.is_down(GamepadButton::North));
+
+        backend.disconnect(1);
+        gamepads.update();
+        assert!(gamepads.is_connected());
+        assert!(gamepads.is_down(GamepadButton::North));
+
+        backend.disconnect(0);
+        gamepads.update();
+        assert!(!gamepads.is_connected());
+        assert!(!gamepads.is_down(GamepadButton::North));
+    }
+}
@@ -0,0 +1,89 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+use macroquad::prelude::*;
+
+use super::gamepad::{GamepadBackend, GamepadButton, GamepadEvent, GamepadState};
+
+/// Native gamepads through gilrs.
+pub struct GilrsBackend {
+    gilrs: Gilrs,
+    // Pads already plugged in when the game starts don't raise a Connected event
+    initial: Vec<GamepadEvent>,
+}
+
+impl GilrsBackend {
+    pub fn new() -> Option<Self> {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(err) => {
+                println!("Failed to initialize gamepads: {}", err);
+                return None;
+            }
+        };
+
+        let initial = gilrs
+            .gamepads()
+            .map(|(id, gamepad)| GamepadEvent::Connected {
+                id: id.into(),
+                name: gamepad.name().to_string(),
+            })
+            .collect();
+
+        Some(Self { gilrs, initial })
+    }
+
+    fn map_button(button: GamepadButton) -> Button {
+        match button {
+            GamepadButton::South => Button::South,
+            GamepadButton::East => Button::East,
+            GamepadButton::West => Button::West,
+            GamepadButton::North => Button::North,
+            GamepadButton::Start => Button::Start,
+            GamepadButton::Select => Button::Select,
+            GamepadButton::LeftShoulder => Button::LeftTrigger,
+            GamepadButton::RightShoulder => Button::RightTrigger,
+            GamepadButton::DPadUp => Button::DPadUp,
+            GamepadButton::DPadDown => Button::DPadDown,
+            GamepadButton::DPadLeft => Button::DPadLeft,
+            GamepadButton::DPadRight => Button::DPadRight,
+        }
+    }
+}
+
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = std::mem::take(&mut self.initial);
+
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::Connected => events.push(GamepadEvent::Connected {
+                    id: event.id.into(),
+                    name: self.gilrs.gamepad(event.id).name().to_string(),
+                }),
+                EventType::Disconnected => events.push(GamepadEvent::Disconnected {
+                    id: event.id.into(),
+                }),
+                _ => {}
+            }
+        }
+
+        events
+    }
+
+    fn state(&self, id: usize) -> Option<GamepadState> {
+        let (_, gamepad) = self.gilrs
+            .gamepads()
+            .find(|(gamepad_id, _)| usize::from(*gamepad_id) == id)?;
+
+        let buttons = GamepadButton::ALL
+            .into_iter()
+            .filter(|button| gamepad.is_pressed(Self::map_button(*button)))
+            .collect();
+
+        // gilrs reports y up, the rest of the input code works in screen space
+        Some(GamepadState {
+            left_stick: vec2(gamepad.value(Axis::LeftStickX), -gamepad.value(Axis::LeftStickY)),
+            right_stick: vec2(gamepad.value(Axis::RightStickX), -gamepad.value(Axis::RightStickY)),
+            buttons,
+        })
+    }
+}
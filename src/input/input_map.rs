@@ -0,0 +1,251 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use super::{Action, Binding, GamepadButton, Gamepads, ResponseCurve};
+use crate::storage::Storage;
+
+const BINDINGS_KEY: &str = "bindings.cfg";
+
+/// Translates physical inputs into actions.
+///
+/// Digital inputs (keyboard, mouse and gamepad buttons) come from the bindings
+/// table. The gamepad sticks are read here too, while other analog sources such
+/// as the touch joystick are handed in by their owners.
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+    gamepads: Gamepads,
+    /// Applied to every analog source, keyboard movement stays digital.
+    pub response_curve: ResponseCurve,
+    revision: u32,
+    storage: Box<dyn Storage>,
+}
+
+impl InputMap {
+    pub fn new(gamepads: Gamepads, storage: Box<dyn Storage>) -> Self {
+        Self {
+            bindings: Self::default_bindings(),
+            gamepads,
+            response_curve: ResponseCurve::Quadratic,
+            revision: 0,
+            storage,
+        }
+    }
+
+    /// Polls the devices that are not tracked by macroquad, once per frame.
+    pub fn update(&mut self) {
+        self.gamepads.update();
+    }
+
+    pub fn gamepads(&self) -> &Gamepads {
+        &self.gamepads
+    }
+
+    /// Loads the persisted bindings, keeping the defaults for anything missing.
+    pub fn load(gamepads: Gamepads, storage: Box<dyn Storage>) -> Self {
+        let mut input = Self::new(gamepads, storage);
+
+        let Some(contents) = input.storage.read(BINDINGS_KEY) else {
+            return input;
+        };
+
+        for line in contents.lines() {
+            let Some((action, bindings)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(action) = Action::from_name(action.trim()) else {
+                continue;
+            };
+
+            let bindings: Vec<Binding> = bindings
+                .split(',')
+                .filter_map(|name| Binding::from_name(name.trim()))
+                .collect();
+
+            if !bindings.is_empty() {
+                input.bindings.insert(action, bindings);
+            }
+        }
+
+        input
+    }
+
+    pub fn save(&mut self) {
+        let contents: String = Action::ALL
+            .iter()
+            .map(|action| {
+                let bindings: Vec<String> = self.bindings(*action).iter().map(Binding::name).collect();
+                format!("{}={}\n", action.name(), bindings.join(","))
+            })
+            .collect();
+
+        if let Err(err) = self.storage.write(BINDINGS_KEY, &contents) {
+            println!("Failed to save bindings: {}", err);
+        }
+    }
+
+    fn default_bindings() -> HashMap<Action, Vec<Binding>> {
+        use Binding::{Gamepad, Key, Mouse};
+
+        HashMap::from([
+            (Action::MoveUp, vec![Key(KeyCode::W), Key(KeyCode::Up), Gamepad(GamepadButton::DPadUp)]),
+            (Action::MoveDown, vec![Key(KeyCode::S), Key(KeyCode::Down), Gamepad(GamepadButton::DPadDown)]),
+            (Action::MoveLeft, vec![Key(KeyCode::A), Key(KeyCode::Left), Gamepad(GamepadButton::DPadLeft)]),
+            (Action::MoveRight, vec![Key(KeyCode::D), Key(KeyCode::Right), Gamepad(GamepadButton::DPadRight)]),
+            (Action::Pause, vec![Key(KeyCode::Escape), Key(KeyCode::Back), Gamepad(GamepadButton::Start)]),
+            (Action::Confirm, vec![Key(KeyCode::Enter), Key(KeyCode::KpEnter), Key(KeyCode::Space), Gamepad(GamepadButton::South)]),
+            (Action::Dash, vec![Key(KeyCode::LeftShift), Mouse(MouseButton::Right), Gamepad(GamepadButton::East)]),
+        ])
+    }
+
+    /// Incremented every time the bindings change, so screens know when to refresh.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Replaces the primary binding of `action`, keeping the alternatives.
+    ///
+    /// The binding moves over from any other action that had it, so one input never
+    /// triggers two actions. Fails with the other action when it is the only binding
+    /// that action has left.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Result<(), Action> {
+        let others = Action::ALL.into_iter().filter(|other| *other != action);
+        if let Some(other) = others.clone().find(|other| self.bindings(*other) == [binding]) {
+            return Err(other);
+        }
+
+        for other in others {
+            if let Some(bindings) = self.bindings.get_mut(&other) {
+                bindings.retain(|existing| *existing != binding);
+            }
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|existing| *existing != binding);
+
+        match bindings.first_mut() {
+            Some(primary) => *primary = binding,
+            None => bindings.push(binding),
+        }
+
+        self.revision += 1;
+        self.save();
+        Ok(())
+    }
+
+    pub fn reset_to_defaults(&mut self) {
+        self.bindings = Self::default_bindings();
+        self.revision += 1;
+        self.save();
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.bindings(action).iter().any(|binding| binding.is_down(&self.gamepads))
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.bindings(action).iter().any(|binding| binding.is_pressed(&self.gamepads))
+    }
+
+    /// Screen-space movement vector (y grows downwards) with a length from 0 to 1.
+    ///
+    /// Analog sources that are being used win over the digital bindings, the
+    /// touch joystick first and then the gamepad left stick. Their magnitude is
+    /// kept through the response curve, digital input is always full speed.
+    pub fn move_axis(&self, analog: Option<Vec2>) -> Vec2 {
+        if let Some(analog) = analog.filter(|dir| dir.length_squared() > 0.0) {
+            return self.response_curve.shape(analog);
+        }
+
+        let stick = self.gamepads.left_stick();
+        if stick.length_squared() > 0.0 {
+            return self.response_curve.shape(stick);
+        }
+
+        let mut move_dir = Vec2::ZERO;
+
+        if self.is_down(Action::MoveRight) {
+            move_dir.x += 1.0;
+        }
+        if self.is_down(Action::MoveLeft) {
+            move_dir.x -= 1.0;
+        }
+        if self.is_down(Action::MoveDown) {
+            move_dir.y += 1.0;
+        }
+        if self.is_down(Action::MoveUp) {
+            move_dir.y -= 1.0;
+        }
+
+        move_dir.normalize_or_zero()
+    }
+
+    /// Screen-space aim direction from the touch aim stick or the gamepad right stick.
+    ///
+    /// `None` when no stick is held, so callers can fall back to automatic aiming.
+    pub fn aim_axis(&self, analog: Option<Vec2>) -> Option<Vec2> {
+        if let Some(analog) = analog.filter(|dir| dir.length_squared() > 0.0) {
+            return Some(analog);
+        }
+
+        let stick = self.gamepads.right_stick();
+        (stick.length_squared() > 0.0).then_some(stick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::SyntheticBackend;
+    use crate::storage::MemoryStorage;
+
+    fn load(storage: &MemoryStorage) -> InputMap {
+        InputMap::load(Gamepads::with_backend(Box::new(SyntheticBackend::default())), Box::new(storage.clone()))
+    }
+
+    #[test]
+    fn rebinding_is_restored_on_the_next_load() {
+        let storage = MemoryStorage::default();
+        load(&storage).rebind(Action::Dash, Binding::Gamepad(GamepadButton::North)).unwrap();
+
+        let bindings = load(&storage).bindings(Action::Dash).to_vec();
+        assert_eq!(bindings[0], Binding::Gamepad(GamepadButton::North));
+        assert_eq!(bindings.len(), 3);
+    }
+
+    #[test]
+    fn rebinding_moves_the_input_off_other_actions() {
+        let mut input = load(&MemoryStorage::default());
+        input.rebind(Action::Dash, Binding::Key(KeyCode::Space)).unwrap();
+
+        assert_eq!(input.bindings(Action::Dash)[0], Binding::Key(KeyCode::Space));
+        assert!(!input.bindings(Action::Confirm).contains(&Binding::Key(KeyCode::Space)));
+        assert_eq!(input.bindings(Action::Confirm)[0], Binding::Key(KeyCode::Enter));
+    }
+
+    #[test]
+    fn rebinding_never_leaves_an_action_unbound() {
+        let mut storage = MemoryStorage::default();
+        storage.write(BINDINGS_KEY, "Pause=Key:P
+").unwrap();
+        let mut input = load(&storage);
+
+        assert_eq!(input.rebind(Action::Dash, Binding::Key(KeyCode::P)), Err(Action::Pause));
+        assert_eq!(input.bindings(Action::Pause), &[Binding::Key(KeyCode::P)]);
+        assert!(!input.bindings(Action::Dash).contains(&Binding::Key(KeyCode::P)));
+        assert_eq!(input.revision(), 0);
+    }
+
+    #[test]
+    fn unknown_lines_keep_the_defaults() {
+        let mut storage = MemoryStorage::default();
+        storage.write(BINDINGS_KEY, "Dash=Key:Nope\nNothing=Key:Q\nPause=Key:P\n").unwrap();
+
+        let input = load(&storage);
+        assert_eq!(input.bindings(Action::Dash), InputMap::default_bindings()[&Action::Dash].as_slice());
+        assert_eq!(input.bindings(Action::Pause), &[Binding::Key(KeyCode::P)]);
+    }
+}
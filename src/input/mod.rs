@@ -0,0 +1,17 @@
+mod action;
+mod binding;
+mod input_map;
+mod gamepad;
+mod analog;
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "gamepad", target_os = "windows", target_os = "macos")))]
+mod gilrs_backend;
+#[cfg(target_arch = "wasm32")]
+mod web_gamepad;
+
+pub use action::Action;
+pub use binding::Binding;
+pub use input_map::InputMap;
+pub use gamepad::{GamepadButton, Gamepads};
+#[cfg(test)]
+pub use gamepad::SyntheticBackend;
+pub use analog::{ResponseCurve, remove_deadzone};
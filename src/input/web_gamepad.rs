@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+
+use super::gamepad::{GamepadBackend, GamepadButton, GamepadEvent, GamepadState};
+
+/// Slots the browser can report, one bit each in `gamepad_connected`.
+const SLOTS: usize = 32;
+
+// Backed by the `gamepad` plugin in `build/gamepad.js`
+extern "C" {
+    /// Bit per slot holding a connected pad with the standard mapping.
+    fn gamepad_connected() -> u32;
+    fn gamepad_button(slot: u32, button: u32) -> u32;
+    fn gamepad_axis(slot: u32, axis: u32) -> f32;
+}
+
+/// Checked by the miniquad loader against the version of the JS plugin.
+#[no_mangle]
+pub extern "C" fn gamepad_crate_version() -> u32 {
+    1
+}
+
+/// Gamepads through the browser's Gamepad API, ids are the browser's slots.
+///
+/// Only pads with the standard mapping are read, its button order is fixed by the spec.
+/// Browsers hide pads until a button is pressed on them.
+pub struct WebGamepadBackend {
+    connected: u32,
+}
+
+impl WebGamepadBackend {
+    pub fn new() -> Self {
+        Self { connected: 0 }
+    }
+
+    /// Index of the button in the standard mapping.
+    fn map_button(button: GamepadButton) -> u32 {
+        match button {
+            GamepadButton::South => 0,
+            GamepadButton::East => 1,
+            GamepadButton::West => 2,
+            GamepadButton::North => 3,
+            GamepadButton::LeftShoulder => 4,
+            GamepadButton::RightShoulder => 5,
+            GamepadButton::Select => 8,
+            GamepadButton::Start => 9,
+            GamepadButton::DPadUp => 12,
+            GamepadButton::DPadDown => 13,
+            GamepadButton::DPadLeft => 14,
+            GamepadButton::DPadRight => 15,
+        }
+    }
+}
+
+impl GamepadBackend for WebGamepadBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        let connected = unsafe { gamepad_connected() };
+        let changed = connected ^ self.connected;
+        self.connected = connected;
+
+        (0..SLOTS)
+            .filter(|slot| changed & (1 << slot) != 0)
+            .map(|id| {
+                if connected & (1 << id) != 0 {
+                    GamepadEvent::Connected { id, name: format!("Browser gamepad {}", id + 1) }
+                } else {
+                    GamepadEvent::Disconnected { id }
+                }
+            })
+            .collect()
+    }
+
+    fn state(&self, id: usize) -> Option<GamepadState> {
+        if id >= SLOTS || self.connected & (1 << id) == 0 {
+            return None;
+        }
+
+        let slot = id as u32;
+        let buttons = GamepadButton::ALL
+            .into_iter()
+            .filter(|button| unsafe { gamepad_button(slot, Self::map_button(*button)) } != 0)
+            .collect();
+
+        // The standard mapping already has y pointing down, like the screen
+        let axis = |axis| unsafe { gamepad_axis(slot, axis) };
+        Some(GamepadState {
+            left_stick: vec2(axis(0), axis(1)),
+            right_stick: vec2(axis(2), axis(3)),
+            buttons,
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use macroquad::prelude::*;
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams};
+
+use crate::materials::VERTEX;
+
+/// Seconds from one noon to the next.
+const DAY_LENGTH: f32 = 240.0;
+/// Ambient light at the darkest point of the first night, later nights get darker.
+const FIRST_NIGHT: f32 = 0.45;
+const DARKEST_NIGHT: f32 = 0.12;
+/// How much darker every night gets than the one before.
+const NIGHT_FALLOFF: f32 = 0.08;
+/// How far from the player enemies can be seen, at the darkest night and at noon.
+const NIGHT_SIGHT: f32 = 450.0;
+const DAY_SIGHT: f32 = 2500.0;
+/// The light map is drawn at a fraction of the screen resolution, lights are soft anyway.
+const LIGHT_MAP_SCALE: f32 = 0.5;
+
+const NIGHT_COLOR: Color = Color::new(0.55, 0.65, 1.0, 1.0);
+const DAY_COLOR: Color = Color::new(1.0, 0.98, 0.92, 1.0);
+
+const FRAGMENT: &str = r#"#version 100
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = color * texture2D(Texture, uv);
+}"#;
+
+/// A soft round light, `position` is its world-space center.
+pub struct Light {
+    pub position: Vec2,
+    pub radius: f32,
+    pub color: Color,
+}
+
+/// Day/night ambient light plus the point lights gathered every frame.
+///
+/// The lights are drawn into a light map that starts out at the ambient color, and the map
+/// is multiplied over the world. Without shader support the world is just dimmed evenly.
+pub struct Lighting {
+    time: f32,
+    lights: Vec<Light>,
+    /// Radial falloff every light is drawn with.
+    glow: Texture2D,
+    additive: Option<Material>,
+    multiply: Option<Material>,
+    target: Option<RenderTarget>,
+    size: (u32, u32),
+}
+
+impl Lighting {
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            lights: Vec::new(),
+            glow: glow_texture(),
+            additive: load("additive", BlendFactor::Value(BlendValue::SourceAlpha), BlendFactor::One),
+            multiply: load("multiply", BlendFactor::Value(BlendValue::DestinationColor), BlendFactor::Zero),
+            target: None,
+            size: (0, 0),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        self.lights.clear();
+    }
+
+    /// 1 at noon, going down to the current night's darkness.
+    fn daylight(&self) -> f32 {
+        let cycle = self.time / DAY_LENGTH;
+        let night = (FIRST_NIGHT - cycle.floor() * NIGHT_FALLOFF).max(DARKEST_NIGHT);
+        let sun = 0.5 + 0.5 * (cycle * std::f32::consts::TAU).cos();
+        night + (1.0 - night) * sun
+    }
+
+    fn ambient(&self) -> Color {
+        let daylight = self.daylight();
+        let tint = Color::new(
+            NIGHT_COLOR.r + (DAY_COLOR.r - NIGHT_COLOR.r) * daylight,
+            NIGHT_COLOR.g + (DAY_COLOR.g - NIGHT_COLOR.g) * daylight,
+            NIGHT_COLOR.b + (DAY_COLOR.b - NIGHT_COLOR.b) * daylight,
+            1.0,
+        );
+        Color::new(tint.r * daylight, tint.g * daylight, tint.b * daylight, 1.0)
+    }
+
+    /// How far from the player enemies can still be made out in the current light.
+    pub fn sight_radius(&self) -> f32 {
+        let darkness = ((1.0 - self.daylight()) / (1.0 - DARKEST_NIGHT)).clamp(0.0, 1.0);
+        DAY_SIGHT + (NIGHT_SIGHT - DAY_SIGHT) * darkness
+    }
+
+    /// Adds a light for this frame.
+    pub fn add(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    /// Draws this frame's lights into the light map, before the world is drawn through
+    /// `camera`.
+    pub fn render(&mut self, camera: &Camera2D) {
+        let (Some(additive), Some(_)) = (&self.additive, &self.multiply) else {
+            return;
+        };
+
+        let size = (
+            (screen_width() * screen_dpi_scale() * LIGHT_MAP_SCALE).max(1.0) as u32,
+            (screen_height() * screen_dpi_scale() * LIGHT_MAP_SCALE).max(1.0) as u32,
+        );
+        if size != self.size || self.target.is_none() {
+            let target = render_target(size.0, size.1);
+            target.texture.set_filter(FilterMode::Linear);
+            self.target = Some(target);
+            self.size = size;
+        }
+
+        // Same view as the world, so the map lines up with it when multiplied on top
+        set_camera(&Camera2D {
+            rotation: camera.rotation,
+            zoom: camera.zoom,
+            target: camera.target,
+            offset: camera.offset,
+            render_target: self.target.clone(),
+            viewport: None,
+        });
+        clear_background(self.ambient());
+
+        gl_use_material(additive);
+        for light in &self.lights {
+            let corner = light.position - Vec2::splat(light.radius);
+            draw_texture_ex(&self.glow, corner.x, corner.y, light.color, DrawTextureParams {
+                dest_size: Some(Vec2::splat(light.radius * 2.0)),
+                ..Default::default()
+            });
+        }
+        gl_use_default_material();
+    }
+
+    /// Multiplies the light map over the world drawn so far, `view` is the world area on
+    /// screen.
+    pub fn apply(&self, view: Rect) {
+        match (&self.multiply, &self.target) {
+            (Some(multiply), Some(target)) => {
+                gl_use_material(multiply);
+                draw_texture_ex(&target.texture, view.x, view.y, WHITE, DrawTextureParams {
+                    dest_size: Some(view.size()),
+                    flip_y: true,
+                    ..Default::default()
+                });
+                gl_use_default_material();
+            }
+            _ => {
+                let darkness = 1.0 - self.daylight();
+                draw_rectangle(view.x, view.y, view.w, view.h, Color::new(0.02, 0.03, 0.1, darkness * 0.8));
+            }
+        }
+    }
+}
+
+/// White in the middle fading to nothing at the edge, brighter near the center.
+fn glow_texture() -> Texture2D {
+    let size = 64;
+    let mut image = Image::gen_image_color(size, size, Color::new(1.0, 1.0, 1.0, 0.0));
+    let center = (size as f32 - 1.0) / 2.0;
+
+    for y in 0..size as u32 {
+        for x in 0..size as u32 {
+            let distance = vec2(x as f32 - center, y as f32 - center).length() / center;
+            let falloff = (1.0 - distance).clamp(0.0, 1.0);
+            image.set_pixel(x, y, Color::new(1.0, 1.0, 1.0, falloff * falloff));
+        }
+    }
+
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
+
+/// Builds a material that draws textures with the given blending.
+fn load(name: &str, source: BlendFactor, destination: BlendFactor) -> Option<Material> {
+    let material = load_material(
+        ShaderSource::Glsl { vertex: VERTEX, fragment: FRAGMENT },
+        MaterialParams {
+            pipeline_params: PipelineParams {
+                color_blend: Some(BlendState::new(Equation::Add, source, destination)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    match material {
+        Ok(material) => Some(material),
+        Err(err) => {
+            println!("Failed to load the {} light material, falling back to plain darkness: {}", name, err);
+            None
+        }
+    }
+}
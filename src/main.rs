@@ -2,27 +2,48 @@
 mod macros;
 
 mod player;
+mod hud;
+mod stats;
+mod combat;
+mod weapons;
+mod projectiles;
+mod pickups;
+mod particles;
+mod damage_numbers;
+mod materials;
+mod post_processing;
+mod lighting;
+mod destructibles;
 mod enemies;
 mod strategies;
 mod constants;
 mod menu;
+mod character_select;
 mod pause;
 mod state;
 mod components;
 mod game;
 mod game_over;
+mod input;
+mod settings;
+mod world;
+mod storage;
 
 use macroquad::prelude::*;
 use macroquad::window;
+use std::rc::Rc;
+use std::cell::RefCell;
 
 use menu::MenuScreen;
+use character_select::CharacterSelectScreen;
 use pause::PauseScreen;
 use game_over::GameOverScreen;
+use settings::{Preferences, SettingsScreen};
+use input::{Action, Gamepads, InputMap};
+use player::load_characters;
 
 use state::GameState;
 use game::Game;
-use components::joystick::Joystick;
-use components::layout::is_mobile;
 
 fn window_conf() -> window::Conf {
     window::Conf {
@@ -34,45 +55,57 @@ fn window_conf() -> window::Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
+    // Buttons and the joystick read touches directly, emulated clicks would fire buttons twice
+    simulate_mouse_with_touch(false);
+
+    let input = Rc::new(RefCell::new(InputMap::load(Gamepads::new(), storage::platform())));
+    let preferences = Rc::new(RefCell::new(Preferences::load(storage::platform())));
+    input.borrow_mut().response_curve = preferences.borrow().response_curve;
+
+    let characters = Rc::new(load_characters().await);
+
     let mut game_state = GameState::Menu;
-    let mut menu_screen = MenuScreen::new();
-    let mut pause_screen = PauseScreen::new();
-    let mut game_over_screen = GameOverScreen::new(); 
-
-    let joystick_size = 200.0;
-    let joystick_pos_x = (screen_width() / 2.0) - (joystick_size / 5.0);
-    let joystick_pos_y = screen_height() - joystick_size - 20.0;
-    let joystick = if is_mobile() {
-        Some(Joystick::new(vec2(joystick_pos_x, joystick_pos_y), joystick_size))
-    } else {
-        None
-    };
-
-    let mut game = Game::new(joystick.clone()).await;
-    game.init().await;
+    let mut menu_screen = MenuScreen::new(Rc::clone(&input));
+    let mut character_select_screen = CharacterSelectScreen::new(Rc::clone(&input), Rc::clone(&characters));
+    let mut pause_screen = PauseScreen::new(Rc::clone(&input));
+    let mut game_over_screen = GameOverScreen::new(Rc::clone(&input)); 
+    let mut settings_screen = SettingsScreen::new(Rc::clone(&input), Rc::clone(&preferences));
+
+    // Built when a run starts, creating it compiles shaders and allocates render targets
+    let mut game: Option<Game> = None;
 
     let mut previous_state = game_state;
 
     loop {
+        input.borrow_mut().update();
+
         match game_state {
             GameState::Menu => {
                 if let Some(next_state) = menu_screen.draw() {
                     game_state = next_state;
                 }
             },
-            GameState::Playing => {
-                if is_key_pressed(KeyCode::Escape) || (is_mobile() && is_key_pressed(KeyCode::Back)) {
-                    game_state = GameState::Paused;
+            GameState::CharacterSelect => {
+                if let Some(next_state) = character_select_screen.draw() {
+                    game_state = next_state;
                 }
+            },
+            GameState::Playing => {
+                if let Some(game) = game.as_mut() {
+                    if input.borrow().is_pressed(Action::Pause) {
+                        pause_screen.set_stats(game.player_stats(), game.world_seed());
+                        game_state = GameState::Paused;
+                    }
 
-                game.update();
+                    game.update();
 
-                if game.is_game_over() {
-                    game_state = GameState::GameOver;
+                    if game.is_game_over() {
+                        game_state = GameState::GameOver;
+                    }
                 }
             },
             GameState::Paused => {
-                if is_key_pressed(KeyCode::Escape) {
+                if input.borrow().is_pressed(Action::Pause) {
                     game_state = GameState::Playing;
                 }
     
@@ -85,12 +118,18 @@ async fn main() {
                     game_state = next_state;
                 }
             },
+            GameState::Settings => {
+                if let Some(next_state) = settings_screen.draw() {
+                    game_state = next_state;
+                }
+            },
         }
 
-        // 💡 Reset the game if returning from Paused orr GameOver to Menu
-        if previous_state != GameState::Menu && game_state == GameState::Menu {
-            game = Game::new(joystick.clone()).await;
-            game.init().await;
+        // 💡 Every run starts fresh with the character that was just picked
+        if previous_state == GameState::CharacterSelect && game_state == GameState::Playing {
+            let mut run = Game::new(Rc::clone(&input), &preferences, character_select_screen.selected()).await;
+            run.init().await;
+            game = Some(run);
         }
 
         previous_state = game_state;
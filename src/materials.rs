@@ -0,0 +1,189 @@
+use macroquad::prelude::*;
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams};
+
+/// Macroquad's default vertex shader, shared by every material.
+pub const VERTEX: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}"#;
+
+/// Pushes the sprite's colors towards white, keeping its shape.
+const FLASH_FRAGMENT: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform float flash;
+
+void main() {
+    vec4 texel = color * texture2D(Texture, uv);
+    gl_FragColor = vec4(mix(texel.rgb, vec3(1.0), flash), texel.a);
+}"#;
+
+/// Eats the sprite away in blocky noise, with a glowing edge where it burns.
+const DISSOLVE_FRAGMENT: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform float progress;
+
+float noise(vec2 cell) {
+    return fract(sin(dot(cell, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    vec4 texel = color * texture2D(Texture, uv);
+    float n = noise(floor(uv * 256.0));
+    if (n < progress) {
+        discard;
+    }
+
+    vec3 edge = vec3(1.0, 0.55, 0.15);
+    gl_FragColor = vec4(mix(texel.rgb, edge, step(n, progress + 0.1)), texel.a);
+}"#;
+
+/// Red that darkens the screen edges, `uv` runs over the whole screen.
+const VIGNETTE_FRAGMENT: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform float intensity;
+
+void main() {
+    float edge = length(uv - vec2(0.5)) * 1.4;
+    float alpha = smoothstep(0.35, 1.0, edge) * intensity;
+    gl_FragColor = vec4(0.8, 0.0, 0.05, alpha);
+}"#;
+
+/// Shaders for damage feedback, each one `None` when it failed to compile so drawing falls
+/// back to plain colors.
+pub struct Materials {
+    flash: Option<Material>,
+    dissolve: Option<Material>,
+    vignette: Option<Material>,
+}
+
+impl Materials {
+    pub fn load() -> Self {
+        Self {
+            flash: load("flash", FLASH_FRAGMENT, "flash"),
+            dissolve: load("dissolve", DISSOLVE_FRAGMENT, "progress"),
+            vignette: load("vignette", VIGNETTE_FRAGMENT, "intensity"),
+        }
+    }
+
+    /// Runs `draw` whitened by `amount` in `0..1`, the fallback lays a white box over `rect`.
+    pub fn draw_flashing(&self, amount: f32, rect: Rect, draw: impl FnOnce()) {
+        if amount <= 0.0 {
+            draw();
+            return;
+        }
+
+        match &self.flash {
+            Some(material) => {
+                material.set_uniform("flash", amount);
+                gl_use_material(material);
+                draw();
+                gl_use_default_material();
+            }
+            None => {
+                draw();
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(1.0, 1.0, 1.0, amount * 0.6));
+            }
+        }
+    }
+
+    /// Runs `draw` with `progress` in `0..1` of it dissolved away.
+    ///
+    /// `draw` gets the opacity to draw with, the fallback fades out instead.
+    pub fn draw_dissolving(&self, progress: f32, draw: impl FnOnce(f32)) {
+        match &self.dissolve {
+            Some(material) => {
+                material.set_uniform("progress", progress);
+                gl_use_material(material);
+                draw(1.0);
+                gl_use_default_material();
+            }
+            None => draw(1.0 - progress),
+        }
+    }
+
+    /// Reddens the screen edges, call with the default camera.
+    pub fn draw_vignette(&self, intensity: f32) {
+        if intensity <= 0.0 {
+            return;
+        }
+
+        match &self.vignette {
+            Some(material) => {
+                material.set_uniform("intensity", intensity);
+                gl_use_material(material);
+                draw_rectangle(0.0, 0.0, screen_width(), screen_height(), WHITE);
+                gl_use_default_material();
+            }
+            None => {
+                // Bands getting fainter towards the middle
+                let color = Color::new(0.8, 0.0, 0.05, 0.0);
+                for band in 0..4 {
+                    let inset = band as f32 * 20.0;
+                    let alpha = intensity * 0.35 * (1.0 - band as f32 / 4.0);
+                    draw_rectangle_lines(
+                        inset,
+                        inset,
+                        screen_width() - inset * 2.0,
+                        screen_height() - inset * 2.0,
+                        40.0,
+                        Color { a: alpha, ..color },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Builds an alpha-blended material with a single float uniform.
+fn load(name: &str, fragment: &str, uniform: &str) -> Option<Material> {
+    let alpha_blend = BlendState::new(
+        Equation::Add,
+        BlendFactor::Value(BlendValue::SourceAlpha),
+        BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+    );
+
+    let material = load_material(
+        ShaderSource::Glsl { vertex: VERTEX, fragment },
+        MaterialParams {
+            uniforms: vec![UniformDesc::new(uniform, UniformType::Float1)],
+            pipeline_params: PipelineParams {
+                color_blend: Some(alpha_blend),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    match material {
+        Ok(material) => Some(material),
+        Err(err) => {
+            println!("Failed to load the {} shader, falling back to plain colors: {}", name, err);
+            None
+        }
+    }
+}
@@ -3,72 +3,98 @@ use std::rc::Rc;
 use std::cell::RefCell;
 
 use crate::state::GameState;
+use crate::input::InputMap;
 use crate::components::button::ButtonBuilder;
 use crate::components::text::TextComponent;
-use crate::components::layout::{Column, is_mobile};
+use crate::components::DrawableComponent;
+use crate::components::focus::FocusManager;
+use crate::components::layout::{Column, Padding, ViewportWatcher, center_on_screen, is_mobile};
 
 pub struct MenuScreen<'a> {
     layout: Column<'a>,
+    viewport: ViewportWatcher,
+    focus: FocusManager,
+    input: Rc<RefCell<InputMap>>,
     state_transition: Rc<RefCell<Option<GameState>>>,
 }
 
 impl<'a> MenuScreen<'a> {
-    pub fn new() -> Self {
+    pub fn new(input: Rc<RefCell<InputMap>>) -> Self {
 
         // Using Rc<RefCell<Option<GameState>>> to allow for shared ownership and interior mutability.
         // Rc (Reference Counted) allows multiple ownership of the same data, and RefCell allows for mutable access to the data inside it.
         // This is useful for the state_transition variable, which will be shared between the MenuScreen and the button's on_click event.
         let state_transition = Rc::new(RefCell::new(None));
 
+        Self {
+            layout: Self::build_layout(&state_transition),
+            viewport: ViewportWatcher::new(),
+            focus: FocusManager::new(),
+            input,
+            state_transition,
+        }
+    }
+
+    fn build_layout(state_transition: &Rc<RefCell<Option<GameState>>>) -> Column<'a> {
+
         // Clonning the Rc<RefCell<Option<GameState>>> to move into the closure
         // for the button's on_click event.
         // This is necessary because the closure needs to own the state_transition
         // variable, and Rc allows us to have multiple owners of the same data.
         // RefCell allows us to mutate the data inside the Rc, even though Rc itself is immutable.
         // This is a common pattern in Rust when dealing with closures and shared state.
-        let state_transition_clone = Rc::clone(&state_transition);
+        let state_transition_clone = Rc::clone(state_transition);
+        let state_settings = Rc::clone(state_transition);
 
-        let screen_center = vec2(screen_width() / 2.0, screen_height() / 2.0);
         let title_size = if is_mobile() { 50.0 } else { 60.0 };
 
         let title = TextComponent::builder()
             .text("Welcome to Metal Against Demons!")
             .font_size(title_size)
             .color(WHITE)
-            .align_center(true)
-            .at(screen_center.x, screen_center.y - 100.0)
             .build();
 
         let start_button_width = if is_mobile() { 500.0 } else { 200.0 };
         let start_button_height = if is_mobile() { 100.0 } else { 60.0 }; 
 
         let start_button = ButtonBuilder::new()
-            .position(
-                screen_center.x - start_button_width / 2.0, 
-                screen_center.y,
-            )
             .size(start_button_width, start_button_height)
             .label("Start Game")
             .on_click(move || {
-                *state_transition_clone.borrow_mut() = Some(GameState::Playing);
+                *state_transition_clone.borrow_mut() = Some(GameState::CharacterSelect);
             })
             .color(Color::from_rgba(90, 20, 20, 255))
             .hover_color(Color::from_rgba(60, 20, 20, 255))
             .build();
 
-        let layout = Column::new()
+        let settings_button = ButtonBuilder::new()
+            .size(start_button_width, start_button_height)
+            .label("Settings")
+            .on_click(move || {
+                *state_settings.borrow_mut() = Some(GameState::Settings);
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        let mut layout = Column::new()
             .centered()
             .spacing(20.0)
-            .add_child(Box::new(title))
-            .add_child(Box::new(start_button));
+            .add_child(Box::new(Padding::new(Box::new(title)).vertical(20.0)))
+            .add_child(Box::new(start_button))
+            .add_child(Box::new(settings_button));
 
-        Self {
-            layout,
-            state_transition,
-        }
+        center_on_screen(&mut layout);
+        layout
     }
 
     pub fn draw(&mut self) -> Option<GameState> {
+        if self.viewport.changed() {
+            self.layout = Self::build_layout(&self.state_transition);
+        }
+
+        self.focus.update(&mut self.layout, &self.input);
+
         clear_background(Color::from_rgba(30, 30, 30, 255));        
         self.layout.draw();
         self.state_transition.borrow_mut().take()
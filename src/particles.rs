@@ -0,0 +1,213 @@
+use macroquad::prelude::*;
+
+/// How a kind of particle looks and moves, shared by every particle it spawns.
+#[derive(Clone, Copy)]
+pub struct ParticleEffect {
+    /// Seconds, picked at random in the range.
+    pub lifetime: (f32, f32),
+    /// World units per second, picked at random in the range.
+    pub speed: (f32, f32),
+    /// Half angle of the cone around the emit direction, in radians.
+    pub spread: f32,
+    /// World-space acceleration, the world y axis points up.
+    pub gravity: Vec2,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub start_size: f32,
+    pub end_size: f32,
+}
+
+pub const HIT_SPARKS: ParticleEffect = ParticleEffect {
+    lifetime: (0.12, 0.25),
+    speed: (250.0, 500.0),
+    spread: 0.7,
+    gravity: Vec2::ZERO,
+    start_color: Color::new(1.0, 0.95, 0.6, 1.0),
+    end_color: Color::new(1.0, 0.5, 0.1, 0.0),
+    start_size: 6.0,
+    end_size: 2.0,
+};
+
+pub const DEATH_BURST: ParticleEffect = ParticleEffect {
+    lifetime: (0.3, 0.6),
+    speed: (120.0, 320.0),
+    spread: std::f32::consts::PI,
+    gravity: vec2(0.0, -500.0),
+    start_color: Color::new(0.75, 0.15, 0.2, 1.0),
+    end_color: Color::new(0.3, 0.05, 0.1, 0.0),
+    start_size: 9.0,
+    end_size: 3.0,
+};
+
+pub const DEBRIS: ParticleEffect = ParticleEffect {
+    lifetime: (0.3, 0.5),
+    speed: (100.0, 260.0),
+    spread: std::f32::consts::PI,
+    gravity: vec2(0.0, -700.0),
+    start_color: Color::new(0.6, 0.42, 0.25, 1.0),
+    end_color: Color::new(0.4, 0.28, 0.15, 0.0),
+    start_size: 8.0,
+    end_size: 4.0,
+};
+
+pub const SPARKLE: ParticleEffect = ParticleEffect {
+    lifetime: (0.4, 0.8),
+    speed: (10.0, 40.0),
+    spread: 0.8,
+    gravity: vec2(0.0, 30.0),
+    start_color: Color::new(1.0, 0.95, 0.6, 1.0),
+    end_color: Color::new(1.0, 1.0, 1.0, 0.0),
+    start_size: 5.0,
+    end_size: 1.0,
+};
+
+pub const DASH_TRAIL: ParticleEffect = ParticleEffect {
+    lifetime: (0.15, 0.3),
+    speed: (20.0, 80.0),
+    spread: 0.5,
+    gravity: Vec2::ZERO,
+    start_color: Color::new(0.6, 0.85, 1.0, 0.8),
+    end_color: Color::new(0.6, 0.85, 1.0, 0.0),
+    start_size: 10.0,
+    end_size: 2.0,
+};
+
+/// Fraction of the budget past which bursts start spawning fewer particles.
+const THROTTLE_START: f32 = 0.6;
+
+#[derive(Clone, Copy)]
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    effect: ParticleEffect,
+}
+
+/// Fixed pool of particles in world space, the capacity is the global budget.
+///
+/// Live particles are packed at the front of the pool so updating and drawing never
+/// walk over dead ones, and nothing is allocated after creation.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    alive: usize,
+}
+
+impl ParticleSystem {
+    pub fn new(budget: usize) -> Self {
+        let dead = Particle {
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            age: 0.0,
+            lifetime: 0.0,
+            effect: HIT_SPARKS,
+        };
+
+        Self {
+            particles: vec![dead; budget],
+            alive: 0,
+        }
+    }
+
+    /// Spawns up to `count` particles at once, thinned out as the budget fills up.
+    ///
+    /// `direction` is the middle of the cone, zero sprays all around.
+    pub fn burst(&mut self, effect: &ParticleEffect, position: Vec2, direction: Vec2, count: usize) {
+        let fill = self.alive as f32 / self.particles.len().max(1) as f32;
+        let scale = if fill > THROTTLE_START {
+            1.0 - (fill - THROTTLE_START) / (1.0 - THROTTLE_START)
+        } else {
+            1.0
+        };
+
+        for _ in 0..(count as f32 * scale).ceil() as usize {
+            self.spawn(effect, position, direction);
+        }
+    }
+
+    fn spawn(&mut self, effect: &ParticleEffect, position: Vec2, direction: Vec2) {
+        if self.alive == self.particles.len() {
+            return;
+        }
+
+        let angle = if direction == Vec2::ZERO {
+            rand::gen_range(-std::f32::consts::PI, std::f32::consts::PI)
+        } else {
+            direction.to_angle() + rand::gen_range(-effect.spread, effect.spread)
+        };
+
+        self.particles[self.alive] = Particle {
+            position,
+            velocity: Vec2::from_angle(angle) * rand::gen_range(effect.speed.0, effect.speed.1),
+            age: 0.0,
+            lifetime: rand::gen_range(effect.lifetime.0, effect.lifetime.1),
+            effect: *effect,
+        };
+        self.alive += 1;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let mut i = 0;
+        while i < self.alive {
+            let particle = &mut self.particles[i];
+            particle.age += dt;
+
+            if particle.age >= particle.lifetime {
+                // Move the last live particle into the gap
+                self.alive -= 1;
+                self.particles.swap(i, self.alive);
+                continue;
+            }
+
+            particle.velocity += particle.effect.gravity * dt;
+            particle.position += particle.velocity * dt;
+            i += 1;
+        }
+    }
+
+    pub fn draw(&self) {
+        for particle in &self.particles[..self.alive] {
+            let t = particle.age / particle.lifetime;
+            let effect = &particle.effect;
+            let size = effect.start_size + (effect.end_size - effect.start_size) * t;
+            let color = Color::new(
+                effect.start_color.r + (effect.end_color.r - effect.start_color.r) * t,
+                effect.start_color.g + (effect.end_color.g - effect.start_color.g) * t,
+                effect.start_color.b + (effect.end_color.b - effect.start_color.b) * t,
+                effect.start_color.a + (effect.end_color.a - effect.start_color.a) * t,
+            );
+
+            draw_rectangle(particle.position.x - size / 2.0, particle.position.y - size / 2.0, size, size, color);
+        }
+    }
+}
+
+/// Spawns particles continuously at a rate, for trails and ambient effects.
+#[derive(Clone, Copy)]
+pub struct Emitter {
+    pub effect: ParticleEffect,
+    /// Particles per second.
+    pub rate: f32,
+    accumulator: f32,
+}
+
+impl Emitter {
+    pub fn new(effect: ParticleEffect, rate: f32) -> Self {
+        Self {
+            effect,
+            rate,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Spawns the particles due over `dt` at `position`.
+    pub fn update(&mut self, particles: &mut ParticleSystem, position: Vec2, direction: Vec2, dt: f32) {
+        self.accumulator += self.rate * dt;
+
+        let count = self.accumulator.floor();
+        self.accumulator -= count;
+        for _ in 0..count as usize {
+            particles.spawn(&self.effect, position, direction);
+        }
+    }
+}
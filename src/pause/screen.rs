@@ -3,44 +3,68 @@ use std::rc::Rc;
 use std::cell::RefCell;
 
 use crate::state::GameState;
+use crate::input::InputMap;
+use crate::stats::Stats;
 use crate::components::button::ButtonBuilder;
 use crate::components::text::TextComponent;
-use crate::components::layout::{Column, is_mobile};
+use crate::components::DrawableComponent;
+use crate::components::focus::FocusManager;
+use crate::components::table::Table;
+use crate::components::layout::{Column, Row, Padding, ViewportWatcher, center_on_screen, is_mobile};
 
 pub struct PauseScreen<'a> {
-    layout: Column<'a>,
+    layout: Row<'a>,
+    stats_rows: Vec<(String, String)>,
+    viewport: ViewportWatcher,
+    focus: FocusManager,
+    input: Rc<RefCell<InputMap>>,
     state_transition: Rc<RefCell<Option<GameState>>>,
 }
 
 impl<'a> PauseScreen<'a> {
-    pub fn new() -> Self {
+    pub fn new(input: Rc<RefCell<InputMap>>) -> Self {
 
         // Using Rc<RefCell<Option<GameState>>> to allow for shared ownership and interior mutability.
         // Rc (Reference Counted) allows multiple ownership of the same data, and RefCell allows for mutable access to the data inside it.
         // This is useful for the state_transition variable, which will be shared between the PauseScreen and the button's on_click event.
         let state_transition = Rc::new(RefCell::new(None));
-        let state_resume = Rc::clone(&state_transition);
-        let state_menu = Rc::clone(&state_transition);
 
-        let screen_center = vec2(screen_width() / 2.0, screen_height() / 2.0);
+        Self {
+            layout: Self::build_layout(&state_transition, &[]),
+            stats_rows: Vec::new(),
+            viewport: ViewportWatcher::new(),
+            focus: FocusManager::new(),
+            input,
+            state_transition,
+        }
+    }
+
+    /// Refreshes the stats panel, called when the game gets paused. Infinite worlds list
+    /// their seed under the stats.
+    pub fn set_stats(&mut self, stats: &Stats, seed: Option<u64>) {
+        self.stats_rows = stats.rows();
+        if let Some(seed) = seed {
+            self.stats_rows.push(("World Seed".to_string(), seed.to_string()));
+        }
+        self.layout = Self::build_layout(&self.state_transition, &self.stats_rows);
+    }
+
+    fn build_layout(state_transition: &Rc<RefCell<Option<GameState>>>, stats_rows: &[(String, String)]) -> Row<'a> {
+        let state_resume = Rc::clone(state_transition);
+        let state_menu = Rc::clone(state_transition);
+
         let title_size = if is_mobile() { 50.0 } else { 60.0 };
 
         let title = TextComponent::builder()
             .text("Paused")
             .font_size(title_size)
             .color(WHITE)
-            .align_center(true)
-            .at(screen_center.x, screen_center.y - 100.0)
             .build();
 
         let button_width = if is_mobile() { 500.0 } else { 200.0 };
         let button_height = if is_mobile() { 100.0 } else { 60.0 };
 
         let resume_button = ButtonBuilder::new()
-            .position(
-                screen_center.x - button_width / 2.0, 
-                screen_center.y,
-            )
             .size(button_width, button_height)
             .label("Resume Game")
             .on_click(move || {
@@ -51,10 +75,6 @@ impl<'a> PauseScreen<'a> {
             .build();
 
         let exit_button = ButtonBuilder::new()
-            .position(
-                screen_center.x - button_width / 2.0, 
-                if is_mobile() { screen_center.y + 120.0 } else { screen_center.y + 80.0 },
-            )
             .size(button_width, button_height)
             .label("Exit Game")
             .on_click(move || {
@@ -64,20 +84,33 @@ impl<'a> PauseScreen<'a> {
             .hover_color(Color::from_rgba(60, 20, 20, 255))
             .build();
 
-        let layout = Column::new()
+        let menu = Column::new()
             .centered()
             .spacing(20.0)
-            .add_child(Box::new(title))
+            .add_child(Box::new(Padding::new(Box::new(title)).vertical(20.0)))
             .add_child(Box::new(resume_button))
             .add_child(Box::new(exit_button));
 
-        Self {
-            layout,
-            state_transition,
-        }
+        let stats_size = if is_mobile() { 36.0 } else { 24.0 };
+        let stats_panel = Table::new(stats_rows.to_vec(), stats_size);
+
+        let mut layout = Row::new()
+            .centered()
+            .spacing(60.0)
+            .add_child(Box::new(menu))
+            .add_child(Box::new(stats_panel));
+
+        center_on_screen(&mut layout);
+        layout
     }
 
     pub fn draw(&mut self) -> Option<GameState> {
+        if self.viewport.changed() {
+            self.layout = Self::build_layout(&self.state_transition, &self.stats_rows);
+        }
+
+        self.focus.update(&mut self.layout, &self.input);
+
         clear_background(Color::from_rgba(30, 30, 30, 255));        
         self.layout.draw();
         self.state_transition.borrow_mut().take()
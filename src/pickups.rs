@@ -0,0 +1,137 @@
+use macroquad::prelude::*;
+
+use crate::particles::{Emitter, ParticleSystem, SPARKLE};
+use crate::player::Player;
+use crate::stats::{Modifier, ModifierKind, Stat};
+
+/// Permanent bonuses a treasure chest can roll.
+const TREASURE_BONUSES: [(Stat, ModifierKind, f32); 6] = [
+    (Stat::Might, ModifierKind::PercentAdditive, 0.15),
+    (Stat::Area, ModifierKind::PercentAdditive, 0.1),
+    (Stat::Cooldown, ModifierKind::PercentAdditive, -0.08),
+    (Stat::MaxHealth, ModifierKind::Flat, 25.0),
+    (Stat::Armor, ModifierKind::Flat, 1.0),
+    (Stat::MoveSpeed, ModifierKind::PercentAdditive, 0.08),
+];
+
+/// A temporary stat bonus, picking up another one for the same stat refreshes it.
+#[derive(Clone, Copy, PartialEq)]
+pub struct PowerUp {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub value: f32,
+    pub duration: f32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PickupKind {
+    /// Dropped by bosses and some elites, grants a random permanent bonus.
+    Treasure,
+    /// Dropped by elites and props, restores some health.
+    Heal(f32),
+    Gold(u32),
+    PowerUp(PowerUp),
+}
+
+pub struct Pickup {
+    /// World-space center.
+    pub position: Vec2,
+    pub kind: PickupKind,
+    sparkle: Emitter,
+}
+
+pub struct Pickups {
+    items: Vec<Pickup>,
+}
+
+impl Pickups {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn spawn(&mut self, position: Vec2, kind: PickupKind) {
+        // Valuables glitter so they stand out in a crowded fight
+        let rate = match kind {
+            PickupKind::Treasure => 12.0,
+            PickupKind::Gold(_) | PickupKind::PowerUp(_) => 4.0,
+            PickupKind::Heal(_) => 0.0,
+        };
+        self.items.push(Pickup { position, kind, sparkle: Emitter::new(SPARKLE, rate) });
+    }
+
+    /// Collects everything within the player's pickup radius, returns the stats treasure
+    /// improved.
+    pub fn update(&mut self, player: &mut Player) -> Vec<Stat> {
+        let center = player.center();
+        let radius = player.stats.get(Stat::PickupRadius);
+
+        let (collected, remaining): (Vec<Pickup>, Vec<Pickup>) = self.items
+            .drain(..)
+            .partition(|pickup| pickup.position.distance(center) <= radius);
+        self.items = remaining;
+
+        let mut treasures = Vec::new();
+        for pickup in collected {
+            match pickup.kind {
+                PickupKind::Treasure => {
+                    let (stat, kind, value) = TREASURE_BONUSES[rand::gen_range(0, TREASURE_BONUSES.len())];
+                    player.stats.add_modifier(Modifier::new(stat, kind, value, "Treasure"));
+                    treasures.push(stat);
+                }
+                PickupKind::Heal(amount) => player.heal(amount),
+                PickupKind::Gold(amount) => player.gold += amount,
+                PickupKind::PowerUp(power_up) => {
+                    let modifier = Modifier::new(power_up.stat, power_up.kind, power_up.value, "PowerUp");
+                    player.stats.add_modifier(modifier.timed(power_up.duration));
+                }
+            }
+        }
+
+        treasures
+    }
+
+    pub fn emit_sparkles(&mut self, particles: &mut ParticleSystem, dt: f32) {
+        for pickup in &mut self.items {
+            let offset = vec2(rand::gen_range(-12.0, 12.0), rand::gen_range(-12.0, 12.0));
+            pickup.sparkle.update(particles, pickup.position + offset, Vec2::Y, dt);
+        }
+    }
+
+    pub fn draw(&self) {
+        let bob = (get_time() * 4.0).sin() as f32 * 4.0;
+
+        for pickup in &self.items {
+            match pickup.kind {
+                PickupKind::Treasure => {
+                    let size = 36.0;
+                    let corner = pickup.position - Vec2::splat(size / 2.0) + vec2(0.0, bob);
+                    draw_rectangle(corner.x, corner.y, size, size, Color::from_rgba(150, 90, 30, 255));
+                    draw_rectangle(corner.x, corner.y + size * 0.45, size, size * 0.12, GOLD);
+                    draw_rectangle_lines(corner.x, corner.y, size, size, 3.0, GOLD);
+                }
+                PickupKind::Heal(_) => {
+                    let center = pickup.position + vec2(0.0, bob);
+                    draw_circle(center.x, center.y, 14.0, Color::from_rgba(220, 40, 70, 255));
+                    draw_rectangle(center.x - 7.0, center.y - 2.0, 14.0, 4.0, WHITE);
+                    draw_rectangle(center.x - 2.0, center.y - 7.0, 4.0, 14.0, WHITE);
+                }
+                PickupKind::Gold(amount) => {
+                    let center = pickup.position + vec2(0.0, bob);
+                    let radius = if amount >= 20 { 13.0 } else { 9.0 };
+                    draw_circle(center.x, center.y, radius, GOLD);
+                    draw_circle_lines(center.x, center.y, radius * 0.6, 2.0, ORANGE);
+                }
+                PickupKind::PowerUp(power_up) => {
+                    let center = pickup.position + vec2(0.0, bob);
+                    let color = match power_up.stat {
+                        Stat::Might => Color::from_rgba(255, 80, 60, 255),
+                        Stat::MoveSpeed => Color::from_rgba(80, 200, 255, 255),
+                        _ => Color::from_rgba(200, 120, 255, 255),
+                    };
+                    draw_poly(center.x, center.y, 4, 16.0, 0.0, color);
+                    draw_poly_lines(center.x, center.y, 4, 16.0, 0.0, 2.0, WHITE);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use macroquad::prelude::*;
+use nanoserde::DeJson;
+use std::collections::HashMap;
+
+use crate::stats::{Modifier, ModifierKind, Stat};
+
+const CHARACTERS_PATH: &str = "data/characters.json";
+
+/// Base stats every character starts from, data files only list what differs.
+const DEFAULT_STATS: [(Stat, f32); 9] = [
+    (Stat::MaxHealth, 200.0),
+    (Stat::MoveSpeed, 3.0),
+    (Stat::Might, 1.0),
+    (Stat::Area, 1.0),
+    (Stat::Cooldown, 1.0),
+    (Stat::Armor, 0.0),
+    (Stat::Regen, 0.0),
+    (Stat::Luck, 1.0),
+    (Stat::PickupRadius, 80.0),
+];
+
+/// A playable character as described in `data/characters.json`.
+#[derive(Clone)]
+pub struct Character {
+    pub name: String,
+    pub description: String,
+    pub sprite: String,
+    pub tint: Color,
+    pub starting_weapon: String,
+    pub base_stats: Vec<(Stat, f32)>,
+    pub passive: Option<Passive>,
+}
+
+/// Permanent bonuses a character starts the run with.
+#[derive(Clone)]
+pub struct Passive {
+    pub description: String,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Default for Character {
+    fn default() -> Self {
+        Self {
+            name: "Knight".to_string(),
+            description: "Sturdy all-rounder".to_string(),
+            sprite: "images/player_spritesheet.png".to_string(),
+            tint: WHITE,
+            starting_weapon: "Sword".to_string(),
+            base_stats: DEFAULT_STATS.to_vec(),
+            passive: None,
+        }
+    }
+}
+
+#[derive(DeJson)]
+struct CharacterData {
+    name: String,
+    description: String,
+    sprite: String,
+    #[nserde(default)]
+    tint: Vec<u8>,
+    starting_weapon: String,
+    #[nserde(default)]
+    stats: HashMap<String, f32>,
+    #[nserde(default)]
+    passive: PassiveData,
+}
+
+#[derive(DeJson, Default)]
+struct PassiveData {
+    description: String,
+    modifiers: Vec<ModifierData>,
+}
+
+#[derive(DeJson)]
+struct ModifierData {
+    stat: String,
+    kind: String,
+    value: f32,
+}
+
+impl CharacterData {
+    fn into_character(self) -> Character {
+        let mut base_stats = DEFAULT_STATS.to_vec();
+
+        for (name, value) in self.stats {
+            match Stat::from_name(&name) {
+                Some(stat) => {
+                    if let Some(entry) = base_stats.iter_mut().find(|(existing, _)| *existing == stat) {
+                        entry.1 = value;
+                    }
+                }
+                None => println!("Unknown stat '{}' for character {}", name, self.name),
+            }
+        }
+
+        let mut modifiers = Vec::new();
+        for modifier in self.passive.modifiers {
+            match (Stat::from_name(&modifier.stat), ModifierKind::from_name(&modifier.kind)) {
+                (Some(stat), Some(kind)) => modifiers.push(Modifier::new(stat, kind, modifier.value, &self.name)),
+                _ => println!("Invalid passive modifier {} {} for character {}", modifier.kind, modifier.stat, self.name),
+            }
+        }
+
+        let passive = (!modifiers.is_empty()).then_some(Passive {
+            description: self.passive.description,
+            modifiers,
+        });
+
+        let tint = match self.tint[..] {
+            [r, g, b] => Color::from_rgba(r, g, b, 255),
+            [r, g, b, a] => Color::from_rgba(r, g, b, a),
+            _ => WHITE,
+        };
+
+        Character {
+            name: self.name,
+            description: self.description,
+            sprite: self.sprite,
+            tint,
+            starting_weapon: self.starting_weapon,
+            base_stats,
+            passive,
+        }
+    }
+}
+
+/// Loads every character definition, falling back to a single default character.
+pub async fn load_characters() -> Vec<Character> {
+    let json = match load_string(CHARACTERS_PATH).await {
+        Ok(json) => json,
+        Err(_) => {
+            println!("Failed to load {}, using the default character", CHARACTERS_PATH);
+            return vec![Character::default()];
+        }
+    };
+
+    match Vec::<CharacterData>::deserialize_json(&json) {
+        Ok(data) if !data.is_empty() => data.into_iter().map(CharacterData::into_character).collect(),
+        Ok(_) => vec![Character::default()],
+        Err(err) => {
+            println!("Failed to parse {}: {}", CHARACTERS_PATH, err);
+            vec![Character::default()]
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use macroquad::prelude::*;
+
+// How long a trail ghost stays visible after being left behind
+const TRAIL_LIFETIME: f32 = 0.2;
+
+struct TrailGhost {
+    position: Vec2,
+    age: f32,
+}
+
+/// Short burst of speed on a cooldown, the player is invulnerable while it lasts.
+pub struct Dash {
+    pub cooldown: f32,
+    pub duration: f32,
+    pub speed: f32,
+    direction: Vec2,
+    active_timer: f32,
+    cooldown_timer: f32,
+    // Full length of the cooldown in progress, after the stat multiplier
+    cooldown_started: f32,
+    trail: Vec<TrailGhost>,
+}
+
+impl Dash {
+    pub fn new() -> Self {
+        Self {
+            cooldown: 1.5,
+            duration: 0.18,
+            speed: 22.0,
+            direction: Vec2::ZERO,
+            active_timer: 0.0,
+            cooldown_timer: 0.0,
+            cooldown_started: 0.0,
+            trail: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active_timer > 0.0
+    }
+
+    /// 0 right after dashing, 1 once the dash can be used again.
+    pub fn readiness(&self) -> f32 {
+        if self.cooldown_started <= 0.0 {
+            return 1.0;
+        }
+
+        1.0 - (self.cooldown_timer / self.cooldown_started).clamp(0.0, 1.0)
+    }
+
+    /// Screen-space direction of the dash in progress.
+    pub fn direction(&self) -> Vec2 {
+        self.direction
+    }
+
+    /// Starts a dash along `direction`, returns false while on cooldown.
+    ///
+    /// `cooldown_multiplier` comes from the player's cooldown stat.
+    pub fn start(&mut self, direction: Vec2, cooldown_multiplier: f32) -> bool {
+        if self.cooldown_timer > 0.0 || direction.length_squared() == 0.0 {
+            return false;
+        }
+
+        self.direction = direction.normalize();
+        self.active_timer = self.duration;
+        self.cooldown_timer = self.cooldown * cooldown_multiplier;
+        self.cooldown_started = self.cooldown_timer;
+        true
+    }
+
+    /// Ticks the timers, leaving a trail ghost at `position` while dashing.
+    pub fn update(&mut self, position: Vec2, dt: f32) {
+        self.active_timer = (self.active_timer - dt).max(0.0);
+        self.cooldown_timer = (self.cooldown_timer - dt).max(0.0);
+
+        for ghost in self.trail.iter_mut() {
+            ghost.age += dt;
+        }
+        self.trail.retain(|ghost| ghost.age < TRAIL_LIFETIME);
+
+        if self.is_active() {
+            self.trail.push(TrailGhost { position, age: 0.0 });
+        }
+    }
+
+    /// Trail ghosts with their opacity, oldest first.
+    pub fn trail(&self) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        self.trail
+            .iter()
+            .map(|ghost| (ghost.position, 1.0 - ghost.age / TRAIL_LIFETIME))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashing_waits_for_the_cooldown() {
+        let mut dash = Dash::new();
+        assert!(dash.start(Vec2::X, 1.0));
+        assert!(!dash.start(Vec2::X, 1.0));
+
+        dash.update(Vec2::ZERO, dash.cooldown - 0.1);
+        assert!(!dash.start(Vec2::X, 1.0));
+
+        dash.update(Vec2::ZERO, 0.2);
+        assert!(dash.start(Vec2::X, 1.0));
+    }
+
+    #[test]
+    fn cooldown_stat_scales_the_wait() {
+        let mut dash = Dash::new();
+        dash.start(Vec2::X, 0.5);
+        dash.update(Vec2::ZERO, dash.cooldown * 0.5);
+        assert!(dash.start(Vec2::X, 0.5));
+    }
+
+    #[test]
+    fn i_frames_end_with_the_dash() {
+        let mut dash = Dash::new();
+        dash.start(Vec2::Y, 1.0);
+        assert!(dash.is_active());
+
+        dash.update(Vec2::ZERO, dash.duration - 0.01);
+        assert!(dash.is_active());
+
+        dash.update(Vec2::ZERO, 0.02);
+        assert!(!dash.is_active());
+    }
+}
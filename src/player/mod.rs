@@ -0,0 +1,383 @@
+mod character;
+mod dash;
+
+use macroquad::prelude::*;
+use crate::stats::{Stat, Stats};
+use crate::weapons::Weapon;
+use crate::world::World;
+use crate::materials::Materials;
+
+pub use character::{Character, load_characters};
+pub use dash::Dash;
+
+// Invulnerability window after taking a hit, so damage comes in discrete hits
+const HIT_INVULNERABILITY: f32 = 0.6;
+/// Seconds the flash and screen vignette take to fade after a hit.
+const HIT_FEEDBACK: f32 = 0.35;
+
+#[derive(PartialEq, Clone, Copy)]
+enum PlayerState {
+    Idle,
+    Walking,
+}
+
+pub struct Player {
+    pub x: f32,
+    pub y: f32,
+    pub health: f32,
+    pub stats: Stats,
+    pub size: f32,
+    /// Screen-space direction from a manual aim stick, `None` while not aiming.
+    pub aim_direction: Option<Vec2>,
+    pub dash: Dash,
+    pub weapon: Weapon,
+    pub gold: u32,
+    /// Health restored and not shown yet, regen trickles in a little every frame.
+    healed: f32,
+    invulnerable_timer: f32,
+    hit_timer: f32,
+    texture: Option<Texture2D>,
+    tint: Color,
+    last_movement: Vec2,
+    current_frame: usize,
+    frame_timer: f32,
+    frame_duration: f32,
+    state: PlayerState,
+    facing_right: bool,
+}
+
+impl Player {
+    pub async fn new(x: f32, y: f32, character: &Character) -> Self {
+        let texture = match load_texture(&character.sprite).await {
+            Ok(t) => Some(t),
+            Err(_) => {
+                println!("Failed to load player texture, falling back to rectangle");
+                None
+            }
+        };
+
+        Self::with_texture(x, y, character, texture)
+    }
+
+    fn with_texture(x: f32, y: f32, character: &Character, texture: Option<Texture2D>) -> Self {
+        let mut stats = Stats::new(&character.base_stats);
+        if let Some(passive) = &character.passive {
+            for modifier in &passive.modifiers {
+                stats.add_modifier(modifier.clone());
+            }
+        }
+
+        Player {
+            x,
+            y,
+            health: stats.get(Stat::MaxHealth),
+            stats,
+            size: 64.0,
+            aim_direction: None,
+            dash: Dash::new(),
+            weapon: Weapon::from_name(&character.starting_weapon),
+            gold: 0,
+            healed: 0.0,
+            invulnerable_timer: 0.0,
+            hit_timer: 0.0,
+            texture,
+            tint: character.tint,
+            last_movement: Vec2::ZERO,
+            current_frame: 0,
+            frame_timer: 0.0,
+            frame_duration: 0.30,
+            state: PlayerState::Idle,
+            facing_right: true,
+        }
+    }
+
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.position() + Vec2::splat(self.size / 2.0)
+    }
+
+    /// Moves the player along a screen-space direction and advances its animation.
+    ///
+    /// A requested dash goes towards the aim stick if held, otherwise along the movement.
+    /// The world's bounds and obstacles stop both walking and dashing.
+    pub fn update(&mut self, direction: Vec2, dash_requested: bool, world: &World) {
+        let dt = get_frame_time();
+        self.tick(dt);
+
+        if dash_requested {
+            let dash_direction = self.aim_direction
+                .or_else(|| (direction.length_squared() > 0.0).then_some(direction))
+                .unwrap_or(if self.last_movement.length_squared() > 0.0 {
+                    self.last_movement
+                } else if self.facing_right {
+                    Vec2::X
+                } else {
+                    -Vec2::X
+                });
+
+            self.dash.start(dash_direction, self.stats.get(Stat::Cooldown));
+        }
+
+        if self.dash.is_active() {
+            let dash_direction = self.dash.direction();
+            self.translate(dash_direction * self.dash.speed, world);
+        } else {
+            self.move_by_direction(direction, world);
+        }
+
+        self.dash.update(self.position(), dt);
+        self.update_animation();
+    }
+
+    /// Runs down the stat, hit and regen timers.
+    fn tick(&mut self, dt: f32) {
+        self.stats.update(dt);
+        self.invulnerable_timer = (self.invulnerable_timer - dt).max(0.0);
+        self.hit_timer = (self.hit_timer - dt).max(0.0);
+        self.heal(self.stats.get(Stat::Regen) * dt);
+    }
+
+    /// World-space direction the player is walking in, zero while idle.
+    pub fn travel_direction(&self) -> Vec2 {
+        match self.state {
+            PlayerState::Walking => vec2(self.last_movement.x, -self.last_movement.y),
+            PlayerState::Idle => Vec2::ZERO,
+        }
+    }
+
+    pub fn max_health(&self) -> f32 {
+        self.stats.get(Stat::MaxHealth)
+    }
+
+    /// Whether hits are ignored, after a hit or while dashing.
+    ///
+    /// The dash keeps its own window so it never stretches the one from a hit.
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_timer > 0.0 || self.dash.is_active()
+    }
+
+    /// 1 right after taking damage, fading to 0.
+    pub fn hit_intensity(&self) -> f32 {
+        self.hit_timer / HIT_FEEDBACK
+    }
+
+    fn update_animation(&mut self) {
+        self.frame_timer += get_frame_time();
+        if self.frame_timer >= self.frame_duration {
+            self.frame_timer = 0.0;
+            self.current_frame = (self.current_frame + 1) % 4;
+        }
+    }
+
+    pub fn draw(&self, materials: &Materials) {
+        for (position, opacity) in self.dash.trail() {
+            self.draw_sprite(position, Color::new(0.6, 0.8, 1.0, opacity * 0.5));
+        }
+
+        // Blink while invulnerable after a hit
+        let blinking = self.invulnerable_timer > 0.0 && !self.dash.is_active() && (get_time() * 20.0) as i64 % 2 == 0;
+        let color = if blinking { Color::new(self.tint.r, self.tint.g, self.tint.b, 0.3) } else { self.tint };
+
+        let rect = Rect::new(self.x, self.y, self.size, self.size);
+        materials.draw_flashing(self.hit_intensity(), rect, || self.draw_sprite(self.position(), color));
+        self.draw_aim_indicator();
+        self.draw_health_bar();
+    }
+
+    fn draw_sprite(&self, position: Vec2, color: Color) {
+        match &self.texture {
+            Some(texture) => {
+                let frame_width = self.size;
+                let frame_height = self.size;
+
+                let row = match (self.state, self.facing_right) {
+                    (PlayerState::Idle, false) => 0,
+                    (PlayerState::Walking, false) => 1,
+                    (PlayerState::Idle, true) => 2,
+                    (PlayerState::Walking, true) => 3,
+                };
+
+                let params = DrawTextureParams {
+                    dest_size: Some(Vec2::new(self.size, self.size)),
+                    flip_x: false,
+                    flip_y: true,
+                    source: Some(Rect {
+                        x: self.current_frame as f32 * frame_width,
+                        y: row as f32 * frame_height,
+                        w: frame_width,
+                        h: frame_height,
+                    }),
+                    ..Default::default()
+                };
+                draw_texture_ex(texture, position.x, position.y, color, params);
+            }
+            None => {
+                let fallback = Color::new(BLUE.r, BLUE.g, BLUE.b, color.a);
+                draw_rectangle(position.x, position.y, self.size, self.size, fallback);
+            }
+        }
+    }
+
+    fn draw_aim_indicator(&self) {
+        let Some(aim) = self.aim_direction else {
+            return;
+        };
+
+        // Aim comes in screen space, the world y axis points up
+        let center = self.center();
+        let world_aim = vec2(aim.x, -aim.y).normalize_or_zero();
+        let tip = center + world_aim * self.size;
+
+        draw_line(center.x, center.y, tip.x, tip.y, 3.0, Color::from_rgba(255, 255, 255, 160));
+        draw_circle(tip.x, tip.y, 5.0, WHITE);
+    }
+
+    pub fn draw_health_bar(&self) {
+
+        if self.health >= self.max_health() {
+            return;
+        }
+
+        let bar_width = self.size / 2.0;
+        let bar_height = 3.0;
+        let bar_x = self.x  + (self.size - bar_width) / 2.0;
+        //let bar_y = self.y + self.size + 5.0;
+        let bar_y = self.y - 10.0;
+
+        // Fundo (cinza)
+        draw_rectangle(bar_x, bar_y, bar_width, bar_height, GRAY);
+
+        // Vida atual (vermelha ou em gradiente)
+        let health_ratio = self.health / self.max_health();
+
+        let health_color = Color::from_rgba(
+            ((1.0 - health_ratio) * 255.0) as u8, // Red aumenta
+            (health_ratio * 255.0) as u8,         // Green diminui
+            0,
+            255,
+        );
+
+        draw_rectangle(bar_x, bar_y, bar_width * health_ratio, bar_height, health_color);
+    }
+
+    /// Moves along a screen-space direction, its length (up to 1) scales the speed.
+    pub fn move_by_direction(&mut self, direction: Vec2, world: &World) {
+        let move_dir = direction.clamp_length_max(1.0);
+    
+        if move_dir.length_squared() > 0.0 {
+            self.last_movement = move_dir.normalize();
+            self.facing_right = move_dir.x >= 0.0;
+            self.state = PlayerState::Walking;
+    
+            self.translate(move_dir * self.stats.get(Stat::MoveSpeed), world);
+        } else {
+            self.state = PlayerState::Idle;
+        }
+    }
+
+    /// Applies a screen-space offset, the world y axis points up.
+    fn translate(&mut self, offset: Vec2, world: &World) {
+        let size = Vec2::splat(self.size);
+        let target = world.clamp(vec2(self.x + offset.x, self.y - offset.y), size);
+
+        let position = world.obstacles.slide(self.position(), target, size);
+        self.x = position.x;
+        self.y = position.y;
+    }
+
+    /// Applies a hit unless the player is still invulnerable from the previous one or a dash.
+    ///
+    /// Armor is subtracted from every hit, but a hit always deals at least 1 damage.
+    /// Returns the damage that actually landed.
+    pub fn take_damage(&mut self, amount: f32) -> f32 {
+        if self.is_invulnerable() {
+            return 0.0;
+        }
+
+        let damage = (amount - self.stats.get(Stat::Armor)).max(1.0).min(self.health);
+        self.invulnerable_timer = HIT_INVULNERABILITY;
+        self.hit_timer = HIT_FEEDBACK;
+        self.health -= damage;
+        if self.health <= 0.0 {
+            self.health = 0.0;
+            self.die();
+        }
+
+        damage
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        let health = (self.health + amount).min(self.max_health());
+        self.healed += health - self.health;
+        self.health = health;
+    }
+
+    /// Health restored since the last call, once it adds up to at least one point.
+    pub fn take_healed(&mut self) -> Option<f32> {
+        if self.healed < 1.0 {
+            return None;
+        }
+
+        Some(std::mem::take(&mut self.healed))
+    }
+
+    pub fn die(&mut self) {
+        println!("Player has died!");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player() -> Player {
+        let character = Character {
+            name: String::new(),
+            description: String::new(),
+            sprite: String::new(),
+            tint: WHITE,
+            starting_weapon: "Sword".to_string(),
+            base_stats: vec![(Stat::MaxHealth, 100.0), (Stat::Cooldown, 1.0)],
+            passive: None,
+        };
+        Player::with_texture(0.0, 0.0, &character, None)
+    }
+
+    fn advance(player: &mut Player, dt: f32) {
+        player.tick(dt);
+        player.dash.update(player.position(), dt);
+    }
+
+    #[test]
+    fn dashing_after_a_hit_keeps_its_invulnerability() {
+        let mut player = player();
+        player.take_damage(10.0);
+        advance(&mut player, 0.2);
+
+        player.dash.start(Vec2::X, 1.0);
+        assert_eq!(player.invulnerable_timer, HIT_INVULNERABILITY - 0.2);
+
+        // Neither reset by the dash nor stretched past the hit's own window
+        advance(&mut player, HIT_INVULNERABILITY - 0.2);
+        assert!(!player.is_invulnerable());
+    }
+
+    #[test]
+    fn dash_outlasting_a_hit_does_not_stretch_it() {
+        let mut player = player();
+        player.take_damage(10.0);
+        advance(&mut player, HIT_INVULNERABILITY - 0.05);
+
+        player.dash.start(Vec2::X, 1.0);
+        advance(&mut player, 0.1);
+        assert!(player.is_invulnerable());
+        assert_eq!(player.invulnerable_timer, 0.0);
+
+        let duration = player.dash.duration;
+        advance(&mut player, duration);
+        assert!(!player.is_invulnerable());
+    }
+}
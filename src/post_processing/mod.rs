@@ -0,0 +1,258 @@
+mod shaders;
+
+use macroquad::prelude::*;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::materials::VERTEX;
+use crate::settings::Preferences;
+
+/// Optional effects applied to the finished frame, in the order they run.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PassKind {
+    Bloom,
+    ColorGrading,
+    /// Only runs for a moment after the player gets hit.
+    ChromaticAberration,
+    Crt,
+}
+
+impl PassKind {
+    pub const ALL: [PassKind; 4] = [
+        PassKind::Bloom,
+        PassKind::ColorGrading,
+        PassKind::ChromaticAberration,
+        PassKind::Crt,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PassKind::Bloom => "Bloom",
+            PassKind::ColorGrading => "Grading",
+            PassKind::ChromaticAberration => "Aberration",
+            PassKind::Crt => "CRT",
+        }
+    }
+
+    /// Parses the `{:?}` name used in the settings file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| format!("{:?}", kind) == name)
+    }
+
+    fn fragment(&self) -> &'static str {
+        match self {
+            PassKind::Bloom => shaders::BLOOM,
+            PassKind::ColorGrading => shaders::COLOR_GRADING,
+            PassKind::ChromaticAberration => shaders::CHROMATIC_ABERRATION,
+            PassKind::Crt => shaders::CRT,
+        }
+    }
+
+    fn uniforms(&self) -> Vec<UniformDesc> {
+        match self {
+            PassKind::Bloom | PassKind::Crt => vec![UniformDesc::new("texel", UniformType::Float2)],
+            PassKind::ColorGrading => Vec::new(),
+            PassKind::ChromaticAberration => vec![UniformDesc::new("strength", UniformType::Float1)],
+        }
+    }
+
+    fn textures(&self) -> Vec<String> {
+        match self {
+            PassKind::ColorGrading => vec!["Lut".to_string()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+struct Pass {
+    kind: PassKind,
+    material: Material,
+}
+
+/// Renders the world offscreen and runs it through the passes enabled in the preferences
+/// before it reaches the screen.
+///
+/// With no pass enabled, or no shader support, the world is drawn straight to the screen.
+pub struct PostProcessing {
+    preferences: Rc<RefCell<Preferences>>,
+    /// Passes whose shader compiled, in running order.
+    passes: Vec<Pass>,
+    /// The scene, then two targets the passes bounce between.
+    targets: Vec<RenderTarget>,
+    size: (u32, u32),
+    /// Passes running this frame, picked in `begin`.
+    active: Vec<usize>,
+    impact: f32,
+}
+
+impl PostProcessing {
+    pub async fn new(preferences: Rc<RefCell<Preferences>>) -> Self {
+        let lut = match load_texture("images/lut.png").await {
+            Ok(texture) => texture,
+            Err(_) => {
+                println!("Failed to load the color grading table, using a neutral one");
+                neutral_lut()
+            }
+        };
+        lut.set_filter(FilterMode::Linear);
+
+        let passes = PassKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let material = load_pass(kind)?;
+                if kind == PassKind::ColorGrading {
+                    material.set_texture("Lut", lut.clone());
+                }
+                Some(Pass { kind, material })
+            })
+            .collect();
+
+        Self {
+            preferences,
+            passes,
+            targets: Vec::new(),
+            size: (0, 0),
+            active: Vec::new(),
+            impact: 0.0,
+        }
+    }
+
+    /// Sets up drawing the world through `camera`, into the offscreen scene when any pass
+    /// is going to run.
+    ///
+    /// `impact` is how hard the player was just hit, from 0 to 1.
+    pub fn begin(&mut self, camera: &Camera2D, impact: f32) {
+        self.impact = impact;
+
+        let preferences = self.preferences.borrow();
+        self.active = (0..self.passes.len())
+            .filter(|&i| {
+                let kind = self.passes[i].kind;
+                preferences.post_passes.contains(&kind) && (kind != PassKind::ChromaticAberration || impact > 0.0)
+            })
+            .collect();
+        drop(preferences);
+
+        if self.active.is_empty() {
+            set_camera(camera);
+            return;
+        }
+
+        self.resize();
+
+        // Macroquad flips the y axis for cameras drawing into a target, so the scene ends
+        // up top row first, the way textures are drawn back onto the screen
+        let scene_camera = Camera2D {
+            rotation: camera.rotation,
+            zoom: camera.zoom,
+            target: camera.target,
+            offset: camera.offset,
+            render_target: Some(self.targets[0].clone()),
+            viewport: None,
+        };
+        set_camera(&scene_camera);
+        clear_background(BLACK);
+    }
+
+    /// Runs the active passes over the scene and puts the result on the screen, leaving the
+    /// default camera set for the UI.
+    pub fn finish(&mut self) {
+        if self.active.is_empty() {
+            set_default_camera();
+            return;
+        }
+
+        let (width, height) = self.size;
+        let texel = vec2(1.0 / width as f32, 1.0 / height as f32);
+        let mut source = 0;
+
+        for (step, &pass) in self.active.iter().enumerate() {
+            let pass = &self.passes[pass];
+            let last = step == self.active.len() - 1;
+
+            match pass.kind {
+                PassKind::Bloom | PassKind::Crt => pass.material.set_uniform("texel", texel),
+                PassKind::ChromaticAberration => pass.material.set_uniform("strength", self.impact),
+                PassKind::ColorGrading => {}
+            }
+
+            // Passes bounce between the two spare targets, the last one draws to the screen
+            let destination = if source == 1 { 2 } else { 1 };
+            let size = if last {
+                set_default_camera();
+                vec2(screen_width(), screen_height())
+            } else {
+                set_camera(&Camera2D {
+                    zoom: vec2(2.0 / width as f32, 2.0 / height as f32),
+                    target: vec2(width as f32 / 2.0, height as f32 / 2.0),
+                    render_target: Some(self.targets[destination].clone()),
+                    ..Default::default()
+                });
+                vec2(width as f32, height as f32)
+            };
+
+            gl_use_material(&pass.material);
+            draw_texture_ex(&self.targets[source].texture, 0.0, 0.0, WHITE, DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            });
+            gl_use_default_material();
+
+            source = destination;
+        }
+    }
+
+    /// Keeps the targets at the window's pixel size.
+    fn resize(&mut self) {
+        let size = (
+            (screen_width() * screen_dpi_scale()) as u32,
+            (screen_height() * screen_dpi_scale()) as u32,
+        );
+        if size == self.size && !self.targets.is_empty() {
+            return;
+        }
+
+        self.size = size;
+        self.targets = (0..3)
+            .map(|_| {
+                let target = render_target(size.0.max(1), size.1.max(1));
+                target.texture.set_filter(FilterMode::Linear);
+                target
+            })
+            .collect();
+    }
+}
+
+fn load_pass(kind: PassKind) -> Option<Material> {
+    let material = load_material(
+        ShaderSource::Glsl { vertex: VERTEX, fragment: kind.fragment() },
+        MaterialParams {
+            uniforms: kind.uniforms(),
+            textures: kind.textures(),
+            ..Default::default()
+        },
+    );
+
+    match material {
+        Ok(material) => Some(material),
+        Err(err) => {
+            println!("Failed to load the {:?} pass, skipping it: {}", kind, err);
+            None
+        }
+    }
+}
+
+/// Table that leaves every color as it is, as 16 slices of 16x16 side by side.
+fn neutral_lut() -> Texture2D {
+    let mut image = Image::gen_image_color(256, 16, WHITE);
+    for blue in 0..16 {
+        for green in 0..16 {
+            for red in 0..16 {
+                let color = Color::new(red as f32 / 15.0, green as f32 / 15.0, blue as f32 / 15.0, 1.0);
+                image.set_pixel(blue * 16 + red, green, color);
+            }
+        }
+    }
+
+    Texture2D::from_image(&image)
+}
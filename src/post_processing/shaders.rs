@@ -0,0 +1,99 @@
+/// Adds a soft glow around the brightest parts of the frame.
+pub const BLOOM: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 texel;
+
+vec3 bright(vec2 at) {
+    vec3 color = texture2D(Texture, at).rgb;
+    return max(color - vec3(0.6), vec3(0.0));
+}
+
+void main() {
+    vec4 base = texture2D(Texture, uv);
+    vec3 glow = vec3(0.0);
+
+    // Two rings of taps stand in for a proper blur, good enough for a glow
+    for (int i = 0; i < 8; i++) {
+        float angle = float(i) * 0.785398;
+        vec2 direction = vec2(cos(angle), sin(angle)) * texel;
+        glow += bright(uv + direction * 4.0) * 0.09;
+        glow += bright(uv + direction * 9.0) * 0.05;
+    }
+
+    gl_FragColor = vec4(base.rgb + glow, base.a);
+}"#;
+
+/// Looks colors up in a 16x16x16 table laid out as 16 slices side by side.
+pub const COLOR_GRADING: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform sampler2D Lut;
+
+vec3 lookup(vec3 color, float slice) {
+    vec2 at = vec2((slice * 16.0 + color.r * 15.0 + 0.5) / 256.0, (color.g * 15.0 + 0.5) / 16.0);
+    return texture2D(Lut, at).rgb;
+}
+
+void main() {
+    vec4 base = texture2D(Texture, uv);
+    vec3 color = clamp(base.rgb, 0.0, 1.0);
+
+    // Blue picks the slice, blend the two nearest ones
+    float blue = color.b * 15.0;
+    float lower = floor(blue);
+    float upper = min(lower + 1.0, 15.0);
+    vec3 graded = mix(lookup(color, lower), lookup(color, upper), blue - lower);
+
+    gl_FragColor = vec4(graded, base.a);
+}"#;
+
+/// Splits the color channels apart towards the edges, `strength` comes from the last hit.
+pub const CHROMATIC_ABERRATION: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform float strength;
+
+void main() {
+    vec2 offset = (uv - vec2(0.5)) * strength * 0.03;
+    float r = texture2D(Texture, uv + offset).r;
+    vec4 g = texture2D(Texture, uv);
+    float b = texture2D(Texture, uv - offset).b;
+
+    gl_FragColor = vec4(r, g.g, b, g.a);
+}"#;
+
+/// Curved screen, scanlines and dark corners of an old monitor.
+pub const CRT: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 texel;
+
+void main() {
+    vec2 centered = uv * 2.0 - 1.0;
+    centered *= 1.0 + dot(centered.yx, centered.yx) * 0.04;
+    vec2 curved = centered * 0.5 + 0.5;
+
+    if (curved.x < 0.0 || curved.x > 1.0 || curved.y < 0.0 || curved.y > 1.0) {
+        gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    vec3 color = texture2D(Texture, curved).rgb;
+    float scanline = 0.85 + 0.15 * sin(curved.y / texel.y * 3.14159);
+    float vignette = 1.0 - dot(centered, centered) * 0.25;
+
+    gl_FragColor = vec4(color * scanline * vignette, 1.0);
+}"#;
@@ -0,0 +1,158 @@
+use macroquad::prelude::*;
+
+use crate::player::Player;
+use crate::strategies::ProjectileCollisionStrategy;
+
+/// How a volley leaves its origin.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FirePattern {
+    /// One shot along a fixed world-space direction, ignoring the target.
+    Straight(Vec2),
+    /// One shot towards the target.
+    Aimed,
+    /// `count` shots fanned out over `angle` radians, centered on the target.
+    Spread { count: usize, angle: f32 },
+    /// One shot that keeps turning towards the target, `turn_rate` in radians per second.
+    Homing { turn_rate: f32 },
+}
+
+#[derive(Clone, Copy)]
+pub struct ProjectileParams {
+    pub speed: f32,
+    pub damage: f32,
+    pub radius: f32,
+    pub lifetime: f32,
+    pub color: Color,
+}
+
+/// A hostile projectile, positions are world-space centers.
+#[derive(Clone, Copy)]
+pub struct Projectile {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+    pub damage: f32,
+    pub remaining: f32,
+    pub color: Color,
+    pub turn_rate: Option<f32>,
+    pub alive: bool,
+}
+
+/// Fixed-size pool of enemy projectiles, slots are reused instead of allocated.
+pub struct ProjectilePool {
+    projectiles: Vec<Projectile>,
+    /// Slots in use, so updates don't walk the whole pool.
+    active: Vec<usize>,
+    free: Vec<usize>,
+    collision_strategy: Box<dyn ProjectileCollisionStrategy>,
+}
+
+impl ProjectilePool {
+    pub fn new(capacity: usize, collision_strategy: Box<dyn ProjectileCollisionStrategy>) -> Self {
+        let dead = Projectile {
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            radius: 0.0,
+            damage: 0.0,
+            remaining: 0.0,
+            color: WHITE,
+            turn_rate: None,
+            alive: false,
+        };
+
+        Self {
+            projectiles: vec![dead; capacity],
+            active: Vec::with_capacity(capacity),
+            free: (0..capacity).rev().collect(),
+            collision_strategy,
+        }
+    }
+
+    /// Fires a volley from `origin`, shots that don't fit in the pool are dropped.
+    pub fn fire(&mut self, origin: Vec2, target: Vec2, pattern: FirePattern, params: ProjectileParams) {
+        let aim = (target - origin).normalize_or(Vec2::X);
+
+        match pattern {
+            FirePattern::Straight(direction) => self.spawn(origin, direction.normalize_or(Vec2::X), None, params),
+            FirePattern::Aimed => self.spawn(origin, aim, None, params),
+            FirePattern::Spread { count, angle } => {
+                let step = if count > 1 { angle / (count - 1) as f32 } else { 0.0 };
+                let start = aim.to_angle() - angle / 2.0;
+
+                for i in 0..count {
+                    let direction = if count > 1 { Vec2::from_angle(start + step * i as f32) } else { aim };
+                    self.spawn(origin, direction, None, params);
+                }
+            }
+            FirePattern::Homing { turn_rate } => self.spawn(origin, aim, Some(turn_rate), params),
+        }
+    }
+
+    fn spawn(&mut self, position: Vec2, direction: Vec2, turn_rate: Option<f32>, params: ProjectileParams) {
+        let Some(slot) = self.free.pop() else {
+            return;
+        };
+
+        self.projectiles[slot] = Projectile {
+            position,
+            velocity: direction * params.speed,
+            radius: params.radius,
+            damage: params.damage,
+            remaining: params.lifetime,
+            color: params.color,
+            turn_rate,
+            alive: true,
+        };
+        self.active.push(slot);
+    }
+
+    pub fn update(&mut self, player: &mut Player) {
+        let dt = get_frame_time();
+        let target = player.center();
+
+        for &slot in &self.active {
+            let projectile = &mut self.projectiles[slot];
+
+            if let Some(turn_rate) = projectile.turn_rate {
+                let current = projectile.velocity.to_angle();
+                let wanted = (target - projectile.position).to_angle();
+                let difference = (wanted - current + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+                let turn = difference.clamp(-turn_rate * dt, turn_rate * dt);
+
+                projectile.velocity = Vec2::from_angle(current + turn) * projectile.velocity.length();
+            }
+
+            projectile.position += projectile.velocity;
+            projectile.remaining -= dt;
+        }
+
+        self.collision_strategy.check_projectiles(&mut self.projectiles, player);
+
+        // Expired shots and the ones the collision strategy spent go back to the pool
+        let projectiles = &mut self.projectiles;
+        let free = &mut self.free;
+        self.active.retain(|&slot| {
+            let projectile = &mut projectiles[slot];
+            projectile.alive &= projectile.remaining > 0.0;
+
+            if !projectile.alive {
+                free.push(slot);
+            }
+            projectile.alive
+        });
+    }
+
+    /// Projectiles in flight.
+    pub fn iter(&self) -> impl Iterator<Item = &Projectile> {
+        self.active.iter().map(|&slot| &self.projectiles[slot])
+    }
+
+    pub fn draw(&self) {
+        for &slot in &self.active {
+            let projectile = &self.projectiles[slot];
+            let position = projectile.position;
+            draw_circle(position.x, position.y, projectile.radius, projectile.color);
+            draw_circle(position.x, position.y, projectile.radius * 0.5, WHITE);
+        }
+    }
+}
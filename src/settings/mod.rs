@@ -0,0 +1,5 @@
+mod screen;
+mod preferences;
+
+pub use screen::SettingsScreen;
+pub use preferences::Preferences;
@@ -0,0 +1,180 @@
+use crate::components::joystick::JoystickMode;
+use crate::input::ResponseCurve;
+use crate::post_processing::PassKind;
+use crate::storage::Storage;
+
+const PREFERENCES_KEY: &str = "settings.cfg";
+/// Touch joystick deadzones the settings screen cycles through.
+const DEADZONE_STEPS: [f32; 5] = [0.05, 0.1, 0.15, 0.2, 0.3];
+
+/// Player preferences edited from the settings screen and persisted between runs.
+pub struct Preferences {
+    pub joystick_mode: JoystickMode,
+    pub response_curve: ResponseCurve,
+    /// Fraction of the touch joystick radius ignored around its center.
+    pub joystick_deadzone: f32,
+    /// Play in an endless generated world instead of the map.
+    pub infinite_world: bool,
+    /// Float damage and healing numbers over enemies and the player.
+    pub damage_numbers: bool,
+    /// Post-processing passes to run, in any order, none unless turned on in the settings.
+    pub post_passes: Vec<PassKind>,
+    revision: u32,
+    storage: Box<dyn Storage>,
+}
+
+impl Preferences {
+    pub fn new(storage: Box<dyn Storage>) -> Self {
+        Self {
+            joystick_mode: JoystickMode::Floating,
+            response_curve: ResponseCurve::Quadratic,
+            joystick_deadzone: 0.15,
+            infinite_world: false,
+            damage_numbers: true,
+            post_passes: Vec::new(),
+            revision: 0,
+            storage,
+        }
+    }
+
+    /// Loads the persisted preferences, keeping the defaults for anything missing.
+    pub fn load(storage: Box<dyn Storage>) -> Self {
+        let mut preferences = Self::new(storage);
+
+        let Some(contents) = preferences.storage.read(PREFERENCES_KEY) else {
+            return preferences;
+        };
+
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            match (key.trim(), value.trim()) {
+                ("joystick_mode", "Fixed") => preferences.joystick_mode = JoystickMode::Fixed,
+                ("joystick_mode", "Floating") => preferences.joystick_mode = JoystickMode::Floating,
+                ("joystick_deadzone", value) => {
+                    if let Ok(deadzone) = value.parse::<f32>() {
+                        preferences.joystick_deadzone = deadzone.clamp(0.0, 0.9);
+                    }
+                }
+                ("infinite_world", value) => preferences.infinite_world = value == "true",
+                ("damage_numbers", value) => preferences.damage_numbers = value == "true",
+                ("post_passes", value) => {
+                    preferences.post_passes = value.split(',').filter_map(PassKind::from_name).collect();
+                }
+                ("response_curve", value) => {
+                    if let Some(curve) = ResponseCurve::from_name(value) {
+                        preferences.response_curve = curve;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        preferences
+    }
+
+    pub fn save(&mut self) {
+        let contents = format!(
+            "joystick_mode={:?}\nresponse_curve={:?}\njoystick_deadzone={}\ninfinite_world={}\ndamage_numbers={}\npost_passes={}\n",
+            self.joystick_mode,
+            self.response_curve,
+            self.joystick_deadzone,
+            self.infinite_world,
+            self.damage_numbers,
+            self.post_passes.iter().map(|kind| format!("{:?}", kind)).collect::<Vec<String>>().join(","),
+        );
+
+        if let Err(err) = self.storage.write(PREFERENCES_KEY, &contents) {
+            println!("Failed to save settings: {}", err);
+        }
+    }
+
+    /// Incremented every time a preference changes, so screens know when to refresh.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    pub fn toggle_joystick_mode(&mut self) {
+        self.joystick_mode = match self.joystick_mode {
+            JoystickMode::Fixed => JoystickMode::Floating,
+            JoystickMode::Floating => JoystickMode::Fixed,
+        };
+        self.changed();
+    }
+
+    pub fn cycle_response_curve(&mut self) {
+        self.response_curve = self.response_curve.next();
+        self.changed();
+    }
+
+    /// Moves to the next larger deadzone step, wrapping around to the smallest.
+    pub fn cycle_joystick_deadzone(&mut self) {
+        self.joystick_deadzone = DEADZONE_STEPS
+            .into_iter()
+            .find(|&step| step > self.joystick_deadzone + 0.001)
+            .unwrap_or(DEADZONE_STEPS[0]);
+        self.changed();
+    }
+
+    pub fn toggle_infinite_world(&mut self) {
+        self.infinite_world = !self.infinite_world;
+        self.changed();
+    }
+
+    pub fn toggle_damage_numbers(&mut self) {
+        self.damage_numbers = !self.damage_numbers;
+        self.changed();
+    }
+
+    pub fn toggle_post_pass(&mut self, kind: PassKind) {
+        match self.post_passes.iter().position(|&enabled| enabled == kind) {
+            Some(index) => {
+                self.post_passes.remove(index);
+            }
+            None => self.post_passes.push(kind),
+        }
+        self.changed();
+    }
+
+    fn changed(&mut self) {
+        self.revision += 1;
+        self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn changes_are_restored_on_the_next_load() {
+        let storage = MemoryStorage::default();
+        let mut preferences = Preferences::load(Box::new(storage.clone()));
+        preferences.toggle_joystick_mode();
+        preferences.cycle_response_curve();
+        preferences.cycle_joystick_deadzone();
+        preferences.toggle_infinite_world();
+        preferences.toggle_post_pass(PassKind::Crt);
+
+        let loaded = Preferences::load(Box::new(storage));
+        assert_eq!(loaded.joystick_mode, preferences.joystick_mode);
+        assert_eq!(loaded.response_curve, preferences.response_curve);
+        assert_eq!(loaded.joystick_deadzone, 0.2);
+        assert!(loaded.infinite_world);
+        assert_eq!(loaded.post_passes, vec![PassKind::Crt]);
+    }
+
+    #[test]
+    fn deadzone_cycles_through_the_steps() {
+        let mut preferences = Preferences::new(Box::new(MemoryStorage::default()));
+        preferences.joystick_deadzone = 0.3;
+
+        preferences.cycle_joystick_deadzone();
+        assert_eq!(preferences.joystick_deadzone, 0.05);
+        preferences.cycle_joystick_deadzone();
+        assert_eq!(preferences.joystick_deadzone, 0.1);
+    }
+}
@@ -0,0 +1,412 @@
+use macroquad::prelude::*;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::state::GameState;
+use crate::input::{Action, Binding, InputMap};
+use crate::components::button::ButtonBuilder;
+use crate::components::text::TextComponent;
+use crate::components::DrawableComponent;
+use crate::components::focus::FocusManager;
+use crate::components::layout::{Column, Padding, Row, Spacer, Stack, ViewportWatcher, center_on_screen, is_mobile};
+use crate::settings::Preferences;
+use crate::post_processing::PassKind;
+
+pub struct SettingsScreen<'a> {
+    layout: Column<'a>,
+    viewport: ViewportWatcher,
+    focus: FocusManager,
+    input: Rc<RefCell<InputMap>>,
+    preferences: Rc<RefCell<Preferences>>,
+    state_transition: Rc<RefCell<Option<GameState>>>,
+    // Action waiting for a new binding, set by the rebind buttons
+    capturing: Rc<RefCell<Option<Action>>>,
+    // Why the last captured input was refused, shown until the capture ends
+    conflict: Option<String>,
+    // Capture state, conflict and revisions the current layout was built with
+    layout_capturing: Option<Action>,
+    layout_conflict: Option<String>,
+    layout_revision: (u32, u32),
+}
+
+impl<'a> SettingsScreen<'a> {
+    pub fn new(input: Rc<RefCell<InputMap>>, preferences: Rc<RefCell<Preferences>>) -> Self {
+        let state_transition = Rc::new(RefCell::new(None));
+        let capturing = Rc::new(RefCell::new(None));
+        let layout_revision = (input.borrow().revision(), preferences.borrow().revision());
+
+        Self {
+            layout: Self::build_layout(&input, &preferences, &state_transition, &capturing, None),
+            viewport: ViewportWatcher::new(),
+            focus: FocusManager::new(),
+            input,
+            preferences,
+            state_transition,
+            capturing,
+            conflict: None,
+            layout_capturing: None,
+            layout_conflict: None,
+            layout_revision,
+        }
+    }
+
+    fn build_layout(
+        input: &Rc<RefCell<InputMap>>,
+        preferences: &Rc<RefCell<Preferences>>,
+        state_transition: &Rc<RefCell<Option<GameState>>>,
+        capturing: &Rc<RefCell<Option<Action>>>,
+        conflict: Option<&str>,
+    ) -> Column<'a> {
+        let mut layout = Self::build_tree(input, preferences, state_transition, capturing, conflict, is_mobile());
+        center_on_screen(&mut layout);
+        layout
+    }
+
+    /// The screen's components, not yet placed anywhere.
+    fn build_tree(
+        input: &Rc<RefCell<InputMap>>,
+        preferences: &Rc<RefCell<Preferences>>,
+        state_transition: &Rc<RefCell<Option<GameState>>>,
+        capturing: &Rc<RefCell<Option<Action>>>,
+        conflict: Option<&str>,
+        mobile: bool,
+    ) -> Column<'a> {
+        let title_size = if mobile { 50.0 } else { 60.0 };
+        let label_size = if mobile { 40.0 } else { 30.0 };
+        let label_width = if mobile { 350.0 } else { 250.0 };
+        let button_width = if mobile { 400.0 } else { 250.0 };
+        let button_height = if mobile { 90.0 } else { 50.0 };
+
+        let title = TextComponent::builder()
+            .text("Settings")
+            .font_size(title_size)
+            .color(WHITE)
+            .build();
+
+        let mut options = Column::new().spacing(10.0);
+
+        for action in Action::ALL {
+            let label = if *capturing.borrow() == Some(action) {
+                "...".to_string()
+            } else {
+                input.borrow()
+                    .bindings(action)
+                    .iter()
+                    .map(Binding::label)
+                    .collect::<Vec<String>>()
+                    .join(" / ")
+            };
+
+            let capture = Rc::clone(capturing);
+            let rebind_button = ButtonBuilder::new()
+                .size(button_width, button_height)
+                .label(label)
+                .on_click(move || {
+                    *capture.borrow_mut() = Some(action);
+                })
+                .color(Color::from_rgba(90, 20, 20, 255))
+                .hover_color(Color::from_rgba(60, 20, 20, 255))
+                .build();
+
+            options = options.add_child(Box::new(
+                Self::setting_row(action.label(), Box::new(rebind_button), label_size, label_width),
+            ));
+        }
+
+        let preferences_toggle = Rc::clone(preferences);
+        let joystick_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label(format!("{:?}", preferences.borrow().joystick_mode))
+            .on_click(move || {
+                preferences_toggle.borrow_mut().toggle_joystick_mode();
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        options = options.add_child(Box::new(
+            Self::setting_row("Joystick", Box::new(joystick_button), label_size, label_width),
+        ));
+
+        // The input map applies the curve, so it is updated together with the preference
+        let preferences_curve = Rc::clone(preferences);
+        let input_curve = Rc::clone(input);
+        let curve_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label(format!("{:?}", preferences.borrow().response_curve))
+            .on_click(move || {
+                let mut preferences = preferences_curve.borrow_mut();
+                preferences.cycle_response_curve();
+                input_curve.borrow_mut().response_curve = preferences.response_curve;
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        options = options.add_child(Box::new(
+            Self::setting_row("Stick Response", Box::new(curve_button), label_size, label_width),
+        ));
+
+        let preferences_deadzone = Rc::clone(preferences);
+        let deadzone_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label(format!("{:.0}%", preferences.borrow().joystick_deadzone * 100.0))
+            .on_click(move || {
+                preferences_deadzone.borrow_mut().cycle_joystick_deadzone();
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        options = options.add_child(Box::new(
+            Self::setting_row("Stick Deadzone", Box::new(deadzone_button), label_size, label_width),
+        ));
+
+        let preferences_world = Rc::clone(preferences);
+        let world_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label(if preferences.borrow().infinite_world { "Infinite" } else { "Map" })
+            .on_click(move || {
+                preferences_world.borrow_mut().toggle_infinite_world();
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        options = options.add_child(Box::new(
+            Self::setting_row("World", Box::new(world_button), label_size, label_width),
+        ));
+
+        let preferences_numbers = Rc::clone(preferences);
+        let numbers_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label(if preferences.borrow().damage_numbers { "On" } else { "Off" })
+            .on_click(move || {
+                preferences_numbers.borrow_mut().toggle_damage_numbers();
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        options = options.add_child(Box::new(
+            Self::setting_row("Damage Numbers", Box::new(numbers_button), label_size, label_width),
+        ));
+
+        // One small toggle per pass, so the effects share a single row
+        let mut passes = Row::new().spacing(10.0);
+        for kind in PassKind::ALL {
+            let enabled = preferences.borrow().post_passes.contains(&kind);
+            let preferences_pass = Rc::clone(preferences);
+            let pass_button = ButtonBuilder::new()
+                .size(button_width * 0.6, button_height)
+                .label(format!("{} {}", kind.label(), if enabled { "On" } else { "Off" }))
+                .on_click(move || {
+                    preferences_pass.borrow_mut().toggle_post_pass(kind);
+                })
+                .color(Color::from_rgba(90, 20, 20, 255))
+                .hover_color(Color::from_rgba(60, 20, 20, 255))
+                .build();
+            passes = passes.add_child(Box::new(pass_button));
+        }
+
+        options = options.add_child(Box::new(
+            Self::setting_row("Effects", Box::new(passes), label_size, label_width),
+        ));
+
+        let input_reset = Rc::clone(input);
+        let reset_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label("Reset Defaults")
+            .on_click(move || {
+                input_reset.borrow_mut().reset_to_defaults();
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        let state_back = Rc::clone(state_transition);
+        let back_button = ButtonBuilder::new()
+            .size(button_width, button_height)
+            .label("Back")
+            .on_click(move || {
+                *state_back.borrow_mut() = Some(GameState::Menu);
+            })
+            .color(Color::from_rgba(90, 20, 20, 255))
+            .hover_color(Color::from_rgba(60, 20, 20, 255))
+            .build();
+
+        let hint = match conflict {
+            Some(conflict) => conflict.to_string(),
+            None if capturing.borrow().is_some() => {
+                "Press a key, mouse or gamepad button, Esc, left click or tap to cancel".to_string()
+            }
+            None => "Select a binding to change it".to_string(),
+        };
+        let hint = TextComponent::builder()
+            .text(hint)
+            .font_size(label_size * 0.8)
+            .color(GRAY)
+            .build();
+
+        Column::new()
+            .centered()
+            .spacing(20.0)
+            .add_child(Box::new(Padding::new(Box::new(title)).vertical(20.0)))
+            .add_child(Box::new(options))
+            .add_child(Box::new(hint))
+            .add_child(Box::new(
+                Row::new()
+                    .spacing(20.0)
+                    .add_child(Box::new(reset_button))
+                    .add_child(Box::new(back_button)),
+            ))
+    }
+
+    /// Label in a fixed width cell followed by its control, so every control lines up.
+    fn setting_row(
+        label: &'a str,
+        control: Box<dyn DrawableComponent + 'a>,
+        label_size: f32,
+        label_width: f32,
+    ) -> Row<'a> {
+        let label = Stack::new()
+            .add_child(Box::new(Spacer::new(label_width, 0.0)))
+            .add_child(Box::new(
+                TextComponent::builder()
+                    .text(label)
+                    .font_size(label_size)
+                    .color(WHITE)
+                    .build(),
+            ));
+
+        Row::new()
+            .centered()
+            .spacing(20.0)
+            .add_child(Box::new(label))
+            .add_child(control)
+    }
+
+    /// Reads the next key, mouse or gamepad button while an action is waiting for a binding.
+    fn capture_binding(&mut self, action: Action) {
+        let binding = if let Some(key) = get_last_key_pressed() {
+            // Escape cancels, unless it is being bound to the pause action
+            if key == KeyCode::Escape && action != Action::Pause {
+                self.end_capture();
+                return;
+            }
+            Binding::is_bindable_key(key).then_some(Binding::Key(key))
+        } else if is_mouse_button_pressed(MouseButton::Left)
+            || touches().iter().any(|touch| touch.phase == TouchPhase::Started)
+        {
+            // Touch screens don't emulate the mouse, so a tap cancels too
+            self.end_capture();
+            return;
+        } else if is_mouse_button_pressed(MouseButton::Right) {
+            Some(Binding::Mouse(MouseButton::Right))
+        } else if is_mouse_button_pressed(MouseButton::Middle) {
+            Some(Binding::Mouse(MouseButton::Middle))
+        } else {
+            self.input.borrow().gamepads().last_pressed().map(Binding::Gamepad)
+        };
+
+        let Some(binding) = binding else {
+            return;
+        };
+
+        // Refused bindings keep the capture going, so another input can be tried
+        let result = self.input.borrow_mut().rebind(action, binding);
+        match result {
+            Ok(()) => self.end_capture(),
+            Err(other) => {
+                self.conflict = Some(format!(
+                    "{} is the only binding for {}, press another",
+                    binding.label(),
+                    other.label(),
+                ));
+            }
+        }
+    }
+
+    fn end_capture(&mut self) {
+        *self.capturing.borrow_mut() = None;
+        self.conflict = None;
+    }
+
+    pub fn draw(&mut self) -> Option<GameState> {
+        let capturing = *self.capturing.borrow();
+
+        if let Some(action) = capturing {
+            self.capture_binding(action);
+        }
+
+        // Button labels show the current values, so the layout follows every change to them
+        let capturing = *self.capturing.borrow();
+        let revision = (self.input.borrow().revision(), self.preferences.borrow().revision());
+        let stale = capturing != self.layout_capturing
+            || self.conflict != self.layout_conflict
+            || revision != self.layout_revision;
+        if self.viewport.changed() || stale {
+            self.layout = Self::build_layout(
+                &self.input,
+                &self.preferences,
+                &self.state_transition,
+                &self.capturing,
+                self.conflict.as_deref(),
+            );
+            self.layout_capturing = capturing;
+            self.layout_conflict = self.conflict.clone();
+            self.layout_revision = revision;
+        }
+
+        if capturing.is_none() {
+            self.focus.update(&mut self.layout, &self.input);
+        }
+
+        clear_background(Color::from_rgba(30, 30, 30, 255));
+        self.layout.draw();
+        self.state_transition.borrow_mut().take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::focus::FocusInput;
+    use crate::components::joystick::JoystickMode;
+    use crate::input::{Gamepads, SyntheticBackend};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn every_button_activates_through_focus() {
+        let gamepads = Gamepads::with_backend(Box::new(SyntheticBackend::default()));
+        let input = Rc::new(RefCell::new(InputMap::new(gamepads, Box::new(MemoryStorage::default()))));
+        let preferences = Rc::new(RefCell::new(Preferences::new(Box::new(MemoryStorage::default()))));
+        let state_transition = Rc::new(RefCell::new(None));
+        let capturing = Rc::new(RefCell::new(None));
+
+        let mut layout = SettingsScreen::build_tree(&input, &preferences, &state_transition, &capturing, None, false);
+        let mut focus = FocusManager::new();
+
+        // The first visit counts the buttons, then every step moves on and confirms, in
+        // layout order up to Back
+        focus.navigate(&mut layout, FocusInput::default());
+        let mut steps = 0;
+        while state_transition.borrow().is_none() && steps < 100 {
+            focus.navigate(&mut layout, FocusInput { next: true, activate: true, previous: false });
+            steps += 1;
+        }
+
+        let preferences = preferences.borrow();
+        assert_eq!(*capturing.borrow(), Some(Action::ALL[Action::ALL.len() - 1]));
+        assert_eq!(preferences.joystick_mode, JoystickMode::Fixed);
+        assert_eq!(input.borrow().response_curve, preferences.response_curve);
+        assert_ne!(preferences.response_curve, crate::input::ResponseCurve::Quadratic);
+        assert_eq!(preferences.joystick_deadzone, 0.2);
+        assert!(preferences.infinite_world);
+        assert!(!preferences.damage_numbers);
+        assert_eq!(preferences.post_passes, PassKind::ALL.to_vec());
+        assert_eq!(input.borrow().revision(), 1);
+        assert_eq!(*state_transition.borrow(), Some(GameState::Menu));
+        assert_eq!(steps, Action::ALL.len() + 11);
+    }
+}
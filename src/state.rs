@@ -1,7 +1,9 @@
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum GameState {
     Menu,
+    CharacterSelect,
     Playing,
     Paused,
-    GameOver
+    GameOver,
+    Settings,
 }
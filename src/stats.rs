@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Stat {
+    MaxHealth,
+    MoveSpeed,
+    /// Damage multiplier.
+    Might,
+    /// Weapon size multiplier.
+    Area,
+    /// Cooldown multiplier, lower is faster.
+    Cooldown,
+    /// Flat damage reduction per hit.
+    Armor,
+    /// Health regenerated per second.
+    Regen,
+    /// Multiplier for critical hits and drops.
+    Luck,
+    PickupRadius,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 9] = [
+        Stat::MaxHealth,
+        Stat::MoveSpeed,
+        Stat::Might,
+        Stat::Area,
+        Stat::Cooldown,
+        Stat::Armor,
+        Stat::Regen,
+        Stat::Luck,
+        Stat::PickupRadius,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stat::MaxHealth => "Max Health",
+            Stat::MoveSpeed => "Move Speed",
+            Stat::Might => "Might",
+            Stat::Area => "Area",
+            Stat::Cooldown => "Cooldown",
+            Stat::Armor => "Armor",
+            Stat::Regen => "Regen",
+            Stat::Luck => "Luck",
+            Stat::PickupRadius => "Pickup Radius",
+        }
+    }
+
+    /// Identifier used in data files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::MaxHealth => "MaxHealth",
+            Stat::MoveSpeed => "MoveSpeed",
+            Stat::Might => "Might",
+            Stat::Area => "Area",
+            Stat::Cooldown => "Cooldown",
+            Stat::Armor => "Armor",
+            Stat::Regen => "Regen",
+            Stat::Luck => "Luck",
+            Stat::PickupRadius => "PickupRadius",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Stat> {
+        Stat::ALL.into_iter().find(|stat| stat.name() == name)
+    }
+
+    /// Formats a value of this stat for the stats panel.
+    pub fn format(&self, value: f32) -> String {
+        match self {
+            Stat::Might | Stat::Area | Stat::Cooldown | Stat::Luck => format!("{:.0}%", value * 100.0),
+            Stat::Regen => format!("{:.1}/s", value),
+            Stat::MoveSpeed => format!("{:.1}", value),
+            Stat::MaxHealth | Stat::Armor | Stat::PickupRadius => format!("{:.0}", value),
+        }
+    }
+}
+
+/// How a modifier combines with the base value.
+///
+/// The final value is `(base + flat) * (1 + sum of additive) * product of (1 + multiplicative)`,
+/// so additive percentages from many sources don't compound while multiplicative ones do.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ModifierKind {
+    Flat,
+    PercentAdditive,
+    PercentMultiplicative,
+}
+
+impl ModifierKind {
+    pub fn from_name(name: &str) -> Option<ModifierKind> {
+        match name {
+            "Flat" => Some(ModifierKind::Flat),
+            "PercentAdditive" => Some(ModifierKind::PercentAdditive),
+            "PercentMultiplicative" => Some(ModifierKind::PercentMultiplicative),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Modifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub value: f32,
+    /// Identifies where the modifier came from, e.g. an item or a buff name.
+    pub source: String,
+    /// Seconds left for timed buffs, `None` for permanent modifiers.
+    pub remaining: Option<f32>,
+}
+
+impl Modifier {
+    pub fn new(stat: Stat, kind: ModifierKind, value: f32, source: &str) -> Self {
+        Self {
+            stat,
+            kind,
+            value,
+            source: source.to_string(),
+            remaining: None,
+        }
+    }
+
+    pub fn timed(mut self, duration: f32) -> Self {
+        self.remaining = Some(duration);
+        self
+    }
+}
+
+/// Base values plus a stack of modifiers.
+///
+/// Permanent modifiers always stack. A timed modifier from a source that already
+/// has one on the same stat refreshes it instead, so re-applying a buff extends it.
+#[derive(Clone)]
+pub struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<Modifier>,
+}
+
+impl Stats {
+    pub fn new(base: &[(Stat, f32)]) -> Self {
+        Self {
+            base: base.iter().copied().collect(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    pub fn base(&self, stat: Stat) -> f32 {
+        self.base.get(&stat).copied().unwrap_or(0.0)
+    }
+
+    pub fn get(&self, stat: Stat) -> f32 {
+        let mut flat = 0.0;
+        let mut additive = 0.0;
+        let mut multiplier = 1.0;
+
+        for modifier in self.modifiers.iter().filter(|modifier| modifier.stat == stat) {
+            match modifier.kind {
+                ModifierKind::Flat => flat += modifier.value,
+                ModifierKind::PercentAdditive => additive += modifier.value,
+                ModifierKind::PercentMultiplicative => multiplier *= 1.0 + modifier.value,
+            }
+        }
+
+        ((self.base(stat) + flat) * (1.0 + additive) * multiplier).max(0.0)
+    }
+
+    pub fn add_modifier(&mut self, modifier: Modifier) {
+        if modifier.remaining.is_some() {
+            let existing = self.modifiers.iter_mut().find(|existing| {
+                existing.remaining.is_some()
+                    && existing.stat == modifier.stat
+                    && existing.source == modifier.source
+            });
+
+            if let Some(existing) = existing {
+                *existing = modifier;
+                return;
+            }
+        }
+
+        self.modifiers.push(modifier);
+    }
+
+    /// Counts timed buffs down, dropping the expired ones.
+    pub fn update(&mut self, dt: f32) {
+        for modifier in self.modifiers.iter_mut() {
+            if let Some(remaining) = modifier.remaining.as_mut() {
+                *remaining -= dt;
+            }
+        }
+
+        self.modifiers.retain(|modifier| modifier.remaining.is_none_or(|remaining| remaining > 0.0));
+    }
+
+    /// Label and formatted current value of every stat, for the stats panel.
+    pub fn rows(&self) -> Vec<(String, String)> {
+        Stat::ALL
+            .iter()
+            .map(|stat| (stat.label().to_string(), stat.format(self.get(*stat))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_applies_before_percentages() {
+        let mut stats = Stats::new(&[(Stat::MaxHealth, 100.0)]);
+        stats.add_modifier(Modifier::new(Stat::MaxHealth, ModifierKind::PercentMultiplicative, 0.5, "relic"));
+        stats.add_modifier(Modifier::new(Stat::MaxHealth, ModifierKind::PercentAdditive, 0.1, "ring"));
+        stats.add_modifier(Modifier::new(Stat::MaxHealth, ModifierKind::Flat, 20.0, "armor"));
+        stats.add_modifier(Modifier::new(Stat::MaxHealth, ModifierKind::PercentAdditive, 0.1, "amulet"));
+
+        // (100 + 20) * (1 + 0.1 + 0.1) * 1.5
+        assert!((stats.get(Stat::MaxHealth) - 216.0).abs() < 1e-3);
+        assert_eq!(stats.base(Stat::MaxHealth), 100.0);
+    }
+
+    #[test]
+    fn multiplicative_percentages_compound() {
+        let mut stats = Stats::new(&[(Stat::Might, 1.0)]);
+        stats.add_modifier(Modifier::new(Stat::Might, ModifierKind::PercentMultiplicative, 1.0, "a"));
+        stats.add_modifier(Modifier::new(Stat::Might, ModifierKind::PercentMultiplicative, 1.0, "b"));
+
+        assert_eq!(stats.get(Stat::Might), 4.0);
+    }
+
+    #[test]
+    fn values_never_go_negative() {
+        let mut stats = Stats::new(&[(Stat::Cooldown, 1.0)]);
+        stats.add_modifier(Modifier::new(Stat::Cooldown, ModifierKind::PercentAdditive, -1.5, "curse"));
+
+        assert_eq!(stats.get(Stat::Cooldown), 0.0);
+    }
+
+    #[test]
+    fn timed_modifiers_expire() {
+        let mut stats = Stats::new(&[(Stat::MoveSpeed, 3.0)]);
+        stats.add_modifier(Modifier::new(Stat::MoveSpeed, ModifierKind::Flat, 1.0, "boots"));
+        stats.add_modifier(Modifier::new(Stat::MoveSpeed, ModifierKind::Flat, 2.0, "haste").timed(1.0));
+        assert_eq!(stats.get(Stat::MoveSpeed), 6.0);
+
+        stats.update(0.6);
+        assert_eq!(stats.get(Stat::MoveSpeed), 6.0);
+
+        stats.update(0.6);
+        assert_eq!(stats.get(Stat::MoveSpeed), 4.0);
+    }
+
+    #[test]
+    fn reapplying_a_timed_modifier_refreshes_it() {
+        let mut stats = Stats::new(&[(Stat::MoveSpeed, 3.0)]);
+        stats.add_modifier(Modifier::new(Stat::MoveSpeed, ModifierKind::Flat, 2.0, "haste").timed(1.0));
+        stats.update(0.8);
+        stats.add_modifier(Modifier::new(Stat::MoveSpeed, ModifierKind::Flat, 2.0, "haste").timed(1.0));
+
+        // Refreshed rather than stacked, and good for a full second again
+        assert_eq!(stats.get(Stat::MoveSpeed), 5.0);
+        stats.update(0.8);
+        assert_eq!(stats.get(Stat::MoveSpeed), 5.0);
+        stats.update(0.3);
+        assert_eq!(stats.get(Stat::MoveSpeed), 3.0);
+    }
+}
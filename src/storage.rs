@@ -0,0 +1,90 @@
+/// Keeps small text files, such as bindings and settings, between runs.
+pub trait Storage {
+    fn read(&self, key: &str) -> Option<String>;
+
+    fn write(&mut self, key: &str, contents: &str) -> Result<(), String>;
+}
+
+/// The browser's localStorage on the web, where there is no file system.
+#[cfg(target_arch = "wasm32")]
+pub fn platform() -> Box<dyn Storage> {
+    Box::new(local_storage::LocalStorage)
+}
+
+/// Files next to the executable.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn platform() -> Box<dyn Storage> {
+    Box::new(FileStorage)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(key).ok()
+    }
+
+    fn write(&mut self, key: &str, contents: &str) -> Result<(), String> {
+        std::fs::write(key, contents).map_err(|err| err.to_string())
+    }
+}
+
+/// Backed by the `storage` plugin in `build/storage.js`.
+#[cfg(target_arch = "wasm32")]
+mod local_storage {
+    use super::Storage;
+
+    extern "C" {
+        /// Byte length of the stored value, or -1 when there is none.
+        fn storage_length(key: *const u8, key_len: usize) -> i32;
+        fn storage_read(key: *const u8, key_len: usize, out: *mut u8);
+        fn storage_write(key: *const u8, key_len: usize, value: *const u8, value_len: usize);
+    }
+
+    /// Checked by the miniquad loader against the version of the JS plugin.
+    #[no_mangle]
+    pub extern "C" fn storage_crate_version() -> u32 {
+        1
+    }
+
+    pub struct LocalStorage;
+
+    impl Storage for LocalStorage {
+        fn read(&self, key: &str) -> Option<String> {
+            let length = unsafe { storage_length(key.as_ptr(), key.len()) };
+            if length < 0 {
+                return None;
+            }
+
+            let mut bytes = vec![0; length as usize];
+            unsafe { storage_read(key.as_ptr(), key.len(), bytes.as_mut_ptr()) };
+            String::from_utf8(bytes).ok()
+        }
+
+        fn write(&mut self, key: &str, contents: &str) -> Result<(), String> {
+            unsafe { storage_write(key.as_ptr(), key.len(), contents.as_ptr(), contents.len()) };
+            Ok(())
+        }
+    }
+}
+
+/// Keeps everything in memory, clones share the same entries.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    entries: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, String>>>,
+}
+
+#[cfg(test)]
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, contents: &str) -> Result<(), String> {
+        self.entries.borrow_mut().insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+}
@@ -3,13 +3,16 @@ use crate::enemies::{EnemyStatus, EnemyData};
 use crate::player::Player;
 use super::CollisionStrategy;
 
+/// Damage of one touch, the player's hit invulnerability spaces touches out.
+const CONTACT_DAMAGE: f32 = 10.0;
+
 pub struct AABBCollision;
 
 impl CollisionStrategy for AABBCollision {
     fn check_collisions(
         &mut self,
         positions: &mut Vec<Vec2>,
-        sizes: &Vec<Vec2>,
+        sizes: &[Vec2],
         data: &mut Vec<EnemyData>,
         player: &mut Player
     ) {
@@ -20,7 +23,8 @@ impl CollisionStrategy for AABBCollision {
 
             let enemy_pos = positions[i];
             let enemy_size = sizes[i];
-            let damage = 1.0; // Replace with the damage[i]
+            let affix = data[i].affix;
+            let damage = CONTACT_DAMAGE * affix.map_or(1.0, |affix| affix.contact_damage);
 
             let overlap = enemy_pos.x < player.x + player.size &&
                           enemy_pos.x + enemy_size.x > player.x &&
@@ -28,7 +32,12 @@ impl CollisionStrategy for AABBCollision {
                           enemy_pos.y + enemy_size.y > player.y;
 
             if overlap {
-                player.take_damage(damage);
+                let dealt = player.take_damage(damage);
+
+                // Vampiric elites heal off the damage that actually landed
+                if let Some(affix) = affix {
+                    data[i].health = (data[i].health + dealt * affix.lifesteal).min(data[i].max_health);
+                }
             }
         }
     }
@@ -0,0 +1,356 @@
+use super::MovementStrategy;
+use macroquad::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use crate::world::NavGrid;
+
+const UNREACHABLE: u32 = u32::MAX;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NEIGHBORS: [(i32, i32, u32); 8] = [
+    (1, 0, STRAIGHT_COST),
+    (-1, 0, STRAIGHT_COST),
+    (0, 1, STRAIGHT_COST),
+    (0, -1, STRAIGHT_COST),
+    (1, 1, DIAGONAL_COST),
+    (1, -1, DIAGONAL_COST),
+    (-1, 1, DIAGONAL_COST),
+    (-1, -1, DIAGONAL_COST),
+];
+
+/// Enemies follow the gradient of a distance field grown from the target's cell.
+///
+/// The field is rebuilt whenever the target changes cell or the grid changes, but only
+/// `cells_per_frame` cells are settled each frame; enemies keep following the previous
+/// field until the new one is complete, so the cost per frame stays flat.
+pub struct FlowFieldMovement {
+    pub max_speed: f32,
+    pub cells_per_frame: usize,
+    /// How much enemies weave around the path, in radians, so they don't walk in single file.
+    pub wobble: f32,
+    /// Enemies closer than this push each other apart, so they don't stack on the same cells.
+    pub separation_distance: f32,
+    pub separation_weight: f32,
+    grid: GridShape,
+    field: Vec<u32>,
+    goal: Option<usize>,
+    building: Vec<u32>,
+    building_goal: Option<usize>,
+    frontier: BinaryHeap<Reverse<(u32, usize)>>,
+    blocked: Vec<bool>,
+    nav_revision: Option<u32>,
+}
+
+/// Enough of the `NavGrid` to map positions to cells without borrowing it.
+#[derive(Clone, Copy, PartialEq)]
+struct GridShape {
+    origin: Vec2,
+    cell_size: f32,
+    width: usize,
+    height: usize,
+}
+
+impl GridShape {
+    fn cell_at(&self, position: Vec2) -> Option<usize> {
+        let local = (position - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn center(&self, cell: usize) -> Vec2 {
+        let (x, y) = (cell % self.width, cell / self.width);
+        self.origin + (vec2(x as f32, y as f32) + 0.5) * self.cell_size
+    }
+
+    fn neighbor(&self, cell: usize, dx: i32, dy: i32) -> Option<usize> {
+        let x = (cell % self.width) as i32 + dx;
+        let y = (cell / self.width) as i32 + dy;
+
+        (x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height)
+            .then(|| y as usize * self.width + x as usize)
+    }
+}
+
+impl FlowFieldMovement {
+    pub fn new(max_speed: f32) -> Self {
+        Self {
+            max_speed,
+            cells_per_frame: 1024,
+            wobble: 0.35,
+            separation_distance: 48.0,
+            separation_weight: 1.5,
+            grid: GridShape {
+                origin: Vec2::ZERO,
+                cell_size: 1.0,
+                width: 0,
+                height: 0,
+            },
+            field: Vec::new(),
+            goal: None,
+            building: Vec::new(),
+            building_goal: None,
+            frontier: BinaryHeap::new(),
+            blocked: Vec::new(),
+            nav_revision: None,
+        }
+    }
+
+    fn start_build(&mut self, goal: usize) {
+        self.building.clear();
+        self.building.resize(self.blocked.len(), UNREACHABLE);
+        self.frontier.clear();
+
+        self.building[goal] = 0;
+        self.frontier.push(Reverse((0, goal)));
+        self.building_goal = Some(goal);
+    }
+
+    /// Settles up to `cells_per_frame` cells of the field being built.
+    fn continue_build(&mut self) {
+        for _ in 0..self.cells_per_frame {
+            let Some(Reverse((cost, cell))) = self.frontier.pop() else {
+                break;
+            };
+            if cost > self.building[cell] {
+                continue;
+            }
+
+            for (dx, dy, step) in NEIGHBORS {
+                let Some(next) = self.walkable_step(cell, dx, dy) else {
+                    continue;
+                };
+
+                let next_cost = cost + step;
+                if next_cost < self.building[next] {
+                    self.building[next] = next_cost;
+                    self.frontier.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        if self.frontier.is_empty() && self.building_goal.is_some() {
+            std::mem::swap(&mut self.field, &mut self.building);
+            self.goal = self.building_goal.take();
+        }
+    }
+
+    /// Neighbor cell if it can be walked into, diagonals can't cut blocked corners.
+    fn walkable_step(&self, cell: usize, dx: i32, dy: i32) -> Option<usize> {
+        let next = self.grid.neighbor(cell, dx, dy)?;
+        if self.blocked[next] {
+            return None;
+        }
+
+        if dx != 0 && dy != 0 {
+            let side_x = self.grid.neighbor(cell, dx, 0)?;
+            let side_y = self.grid.neighbor(cell, 0, dy)?;
+            if self.blocked[side_x] || self.blocked[side_y] {
+                return None;
+            }
+        }
+
+        Some(next)
+    }
+
+    /// Direction towards the neighbor closest to the goal, `None` where the field can't help.
+    fn downhill(&self, position: Vec2) -> Option<Vec2> {
+        let cell = self.grid.cell_at(position)?;
+        if self.goal == Some(cell) || self.field.get(cell).is_none_or(|&cost| cost == UNREACHABLE) {
+            return None;
+        }
+
+        let mut best = (self.field[cell], None);
+        for (dx, dy, _) in NEIGHBORS {
+            if let Some(next) = self.walkable_step(cell, dx, dy) {
+                if self.field[next] < best.0 {
+                    best = (self.field[next], Some(next));
+                }
+            }
+        }
+
+        best.1.map(|next| (self.grid.center(next) - position).normalize_or_zero())
+    }
+
+    /// Push away from the enemies crowding `position`, stronger the closer they are.
+    fn separation(&self, position: Vec2, index: usize, all_positions: &[Vec2]) -> Vec2 {
+        let mut push = Vec2::ZERO;
+
+        for (i, &other) in all_positions.iter().enumerate() {
+            let distance = position.distance(other);
+            if i == index || distance >= self.separation_distance {
+                continue;
+            }
+
+            // Enemies on the exact same spot split by index rather than not at all
+            let away = (position - other).try_normalize().unwrap_or_else(|| {
+                if i < index { Vec2::X } else { -Vec2::X }
+            });
+            push += away * (1.0 - distance / self.separation_distance).powi(2);
+        }
+
+        push * self.separation_weight
+    }
+}
+
+impl MovementStrategy for FlowFieldMovement {
+    fn prepare(&mut self, target: Vec2, nav: &NavGrid) {
+        let shape = GridShape {
+            origin: nav.origin,
+            cell_size: nav.cell_size,
+            width: nav.width,
+            height: nav.height,
+        };
+
+        // A moved or resized grid invalidates every cell index
+        let reshaped = shape != self.grid;
+        if reshaped {
+            self.grid = shape;
+            self.field = vec![UNREACHABLE; shape.width * shape.height];
+            self.goal = None;
+            self.building_goal = None;
+        }
+
+        let changed = reshaped || self.nav_revision != Some(nav.revision());
+        if changed {
+            self.nav_revision = Some(nav.revision());
+            self.blocked = (0..shape.width * shape.height)
+                .map(|cell| nav.is_blocked(cell % shape.width, cell / shape.width))
+                .collect();
+        }
+
+        if let Some(goal) = shape.cell_at(target) {
+            let pending = self.building_goal.or(self.goal);
+            if changed || pending != Some(goal) {
+                self.start_build(goal);
+            }
+        }
+
+        self.continue_build();
+    }
+
+    fn move_enemy(
+        &self,
+        position: &mut Vec2,
+        target: Vec2,
+        time: f32,
+        index: usize,
+        all_positions: &[Vec2],
+        speed_multiplier: f32,
+    ) {
+        let direction = self.downhill(*position)
+            .unwrap_or_else(|| (target - *position).normalize_or_zero());
+
+        let weave = (time * 2.0 + index as f32 * 1.7).sin() * self.wobble;
+        let steering = Vec2::from_angle(weave).rotate(direction);
+        let velocity = (steering + self.separation(*position, index, all_positions)).clamp_length_max(1.0);
+
+        *position += velocity * self.max_speed * speed_multiplier;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10x10 grid of 10 unit cells with a wall down column 5, open only at the top two rows.
+    fn walled_grid() -> NavGrid {
+        let mut nav = NavGrid::new(Vec2::ZERO, 10.0, 10, 10);
+        nav.set_blocked_rect(Rect::new(50.0, 0.0, 10.0, 80.0), true);
+        nav
+    }
+
+    fn settled(nav: &NavGrid, target: Vec2) -> FlowFieldMovement {
+        let mut movement = FlowFieldMovement::new(1.0);
+        movement.wobble = 0.0;
+        movement.prepare(target, nav);
+        movement
+    }
+
+    #[test]
+    fn field_leads_around_the_wall() {
+        let nav = walled_grid();
+        let target = vec2(85.0, 25.0);
+        let movement = settled(&nav, target);
+
+        // Straight at the target would walk into the wall, the field heads for the gap
+        let direction = movement.downhill(vec2(25.0, 25.0)).unwrap();
+        assert!(direction.y > 0.5, "{:?}", direction);
+    }
+
+    #[test]
+    fn enemies_reach_the_target_without_entering_the_wall() {
+        let nav = walled_grid();
+        let target = vec2(85.0, 25.0);
+        let movement = settled(&nav, target);
+        let mut position = vec2(25.0, 25.0);
+
+        for step in 0..400 {
+            movement.move_enemy(&mut position, target, 0.0, 0, &[], 1.0);
+
+            let cell = (position / 10.0).floor();
+            assert!(!nav.is_blocked(cell.x as usize, cell.y as usize), "in the wall at {:?}", position);
+
+            if position.distance(target) < 2.0 {
+                assert!(step > 60, "went through the wall instead of around it");
+                return;
+            }
+        }
+
+        panic!("never reached the target, stopped at {:?}", position);
+    }
+
+    #[test]
+    fn walled_off_cells_fall_back_to_walking_straight() {
+        let mut nav = NavGrid::new(Vec2::ZERO, 10.0, 10, 10);
+        nav.set_blocked_rect(Rect::new(50.0, 0.0, 10.0, 100.0), true);
+        let movement = settled(&nav, vec2(85.0, 25.0));
+
+        assert_eq!(movement.downhill(vec2(25.0, 25.0)), None);
+    }
+
+    #[test]
+    fn enemies_on_the_same_cell_push_apart() {
+        let nav = NavGrid::new(Vec2::ZERO, 10.0, 10, 10);
+        let target = vec2(22.0, 95.0);
+        let movement = settled(&nav, target);
+
+        // Both in cell (2, 2), the field alone would steer them into the same cell above
+        let mut positions = vec![vec2(21.0, 25.0), vec2(23.0, 25.0)];
+        let before = positions[0].distance(positions[1]);
+
+        let all_positions = positions.clone();
+        for (i, position) in positions.iter_mut().enumerate() {
+            movement.move_enemy(position, target, 0.0, i, &all_positions, 1.0);
+        }
+        assert!(positions[0].distance(positions[1]) > before, "{:?}", positions);
+
+        // Even when they stand on the exact same spot
+        let all_positions = vec![vec2(25.0, 25.0); 2];
+        let mut first = all_positions[0];
+        let mut second = all_positions[1];
+        movement.move_enemy(&mut first, target, 0.0, 0, &all_positions, 1.0);
+        movement.move_enemy(&mut second, target, 0.0, 1, &all_positions, 1.0);
+        assert!(first.distance(second) > 0.0);
+    }
+
+    #[test]
+    fn large_fields_are_built_over_several_frames() {
+        let nav = walled_grid();
+        let mut movement = FlowFieldMovement::new(1.0);
+        movement.cells_per_frame = 10;
+
+        movement.prepare(vec2(85.0, 25.0), &nav);
+        assert_eq!(movement.goal, None);
+
+        for _ in 0..20 {
+            movement.prepare(vec2(85.0, 25.0), &nav);
+        }
+        assert!(movement.goal.is_some());
+        assert!(movement.downhill(vec2(25.0, 25.0)).is_some());
+    }
+}
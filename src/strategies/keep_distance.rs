@@ -0,0 +1,38 @@
+use super::MovementStrategy;
+use macroquad::prelude::*;
+
+/// Ranged enemies hover around a preferred distance from the target and circle it.
+pub struct KeepDistanceMovement {
+    pub preferred_distance: f32,
+    /// Distance around the preferred one where the enemy only strafes.
+    pub tolerance: f32,
+    pub max_speed: f32,
+}
+
+impl MovementStrategy for KeepDistanceMovement {
+    fn move_enemy(
+        &self,
+        position: &mut Vec2,
+        target: Vec2,
+        _time: f32,
+        index: usize,
+        _all_positions: &[Vec2],
+        speed_multiplier: f32,
+    ) {
+        let to_target = target - *position;
+        let distance = to_target.length();
+        let direction = to_target.normalize_or_zero();
+
+        let velocity = if distance > self.preferred_distance + self.tolerance {
+            direction
+        } else if distance < self.preferred_distance - self.tolerance {
+            -direction
+        } else {
+            // Half of them circle each way so they don't bunch up
+            let side = if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+            direction.perp() * side * 0.5
+        };
+
+        *position += velocity * self.max_speed * speed_multiplier;
+    }
+}
@@ -1,32 +1,48 @@
-mod boids;
+mod flow_field;
+mod keep_distance;
 mod collisions;
+mod projectile_collisions;
 
-pub use boids::BoidsMovement;
+pub use flow_field::FlowFieldMovement;
+pub use keep_distance::KeepDistanceMovement;
 pub use collisions::AABBCollision;
+pub use projectile_collisions::CircleCollision;
 
 use macroquad::prelude::*;
 use crate::enemies::{EnemyData};
 use crate::player::Player;
+use crate::projectiles::Projectile;
+use crate::world::NavGrid;
 
-#[allow(dead_code)]
 pub trait MovementStrategy: Send + Sync {
+    /// Called once per frame before any enemy moves, for strategies that cache world state.
+    fn prepare(&mut self, _target: Vec2, _nav: &NavGrid) {}
+
     fn move_enemy(
         &self, 
         position: &mut Vec2,
         target: Vec2,
         time: f32,
         index: usize,
-        all_positions: &[Vec2]
+        all_positions: &[Vec2],
+        speed_multiplier: f32,
     );
 }
 
-#[allow(dead_code)]
 pub trait CollisionStrategy {
     fn check_collisions(
         &mut self,
         positions: &mut Vec<Vec2>,
-        sizes: &Vec<Vec2>,
+        sizes: &[Vec2],
         data: &mut Vec<EnemyData>,
         player: &mut Player
     );
+}
+
+pub trait ProjectileCollisionStrategy {
+    fn check_projectiles(
+        &mut self,
+        projectiles: &mut [Projectile],
+        player: &mut Player
+    );
 }
\ No newline at end of file
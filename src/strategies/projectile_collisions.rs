@@ -0,0 +1,29 @@
+use macroquad::prelude::*;
+use crate::projectiles::Projectile;
+use crate::player::Player;
+use crate::combat::rect_overlaps_circle;
+use super::ProjectileCollisionStrategy;
+
+/// Circle projectiles against the player's box, each projectile hits once.
+///
+/// Every projectile overlapping the player is spent, only the first one deals damage
+/// since the hit invulnerability absorbs the rest.
+pub struct CircleCollision;
+
+impl ProjectileCollisionStrategy for CircleCollision {
+    fn check_projectiles(&mut self, projectiles: &mut [Projectile], player: &mut Player) {
+        // Dashing through bullets is the point of the i-frames, so they keep flying
+        if player.is_invulnerable() {
+            return;
+        }
+
+        let player_size = Vec2::splat(player.size);
+
+        for projectile in projectiles.iter_mut().filter(|projectile| projectile.alive) {
+            if rect_overlaps_circle(player.position(), player_size, projectile.position, projectile.radius) {
+                player.take_damage(projectile.damage);
+                projectile.alive = false;
+            }
+        }
+    }
+}
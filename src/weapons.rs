@@ -0,0 +1,304 @@
+use macroquad::prelude::*;
+
+use crate::combat::{Damageable, Hit, TargetLayer};
+use crate::enemies::StatusEffect;
+use crate::stats::{Stat, Stats};
+
+/// Chance of a shot's hit being critical before luck.
+const CRIT_CHANCE: f32 = 0.1;
+const CRIT_MULTIPLIER: f32 = 2.0;
+
+/// Automatic weapon that periodically fires shots from the player.
+pub struct Weapon {
+    damage: f32,
+    cooldown: f32,
+    speed: f32,
+    lifetime: f32,
+    radius: f32,
+    pierce: usize,
+    /// How far away a target can be for the weapon to fire on its own.
+    pub range: f32,
+    color: Color,
+    /// Effects applied on hit, each with its chance to proc.
+    effects: Vec<(StatusEffect, f32)>,
+    timer: f32,
+}
+
+impl Weapon {
+    /// Builds one of the known weapons, unknown names fall back to the sword.
+    pub fn from_name(name: &str) -> Self {
+        let (damage, cooldown, speed, lifetime, radius, pierce, color) = match name {
+            "Sword" => (25.0, 0.8, 10.0, 0.25, 28.0, usize::MAX, LIGHTGRAY),
+            "Axe" => (40.0, 1.3, 7.0, 0.8, 22.0, 3, ORANGE),
+            "Crossbow" => (18.0, 0.5, 16.0, 1.2, 8.0, 1, YELLOW),
+            _ => {
+                println!("Unknown weapon '{}', using the sword", name);
+                return Self::from_name("Sword");
+            }
+        };
+
+        // Knockback impulses only carry a strength here, shots point them along their flight
+        let effects = match name {
+            "Sword" => vec![
+                (StatusEffect::Knockback { impulse: vec2(8.0, 0.0) }, 1.0),
+                (StatusEffect::Freeze { duration: 1.0 }, 0.1),
+            ],
+            "Axe" => vec![
+                (StatusEffect::Burn { dps: 10.0, duration: 2.0 }, 1.0),
+                (StatusEffect::Knockback { impulse: vec2(4.0, 0.0) }, 1.0),
+            ],
+            _ => vec![
+                (StatusEffect::Poison { dps: 4.0, duration: 3.0 }, 1.0),
+                (StatusEffect::Slow { amount: 0.4, duration: 1.5 }, 1.0),
+            ],
+        };
+
+        Self {
+            damage,
+            cooldown,
+            speed,
+            lifetime,
+            radius,
+            pierce,
+            range: speed * 60.0 * lifetime + 200.0,
+            color,
+            effects,
+            timer: 0.0,
+        }
+    }
+
+    /// Counts the cooldown down and fires along a world-space direction when ready.
+    pub fn update(&mut self, origin: Vec2, direction: Option<Vec2>, stats: &Stats, shots: &mut ShotSystem) {
+        self.timer -= get_frame_time();
+
+        let Some(direction) = direction.filter(|direction| direction.length_squared() > 0.0) else {
+            return;
+        };
+
+        if self.timer > 0.0 {
+            return;
+        }
+
+        self.timer = self.cooldown * stats.get(Stat::Cooldown);
+        let direction = direction.normalize();
+        let luck = stats.get(Stat::Luck);
+
+        shots.spawn(Shot {
+            position: origin,
+            velocity: direction * self.speed,
+            damage: self.damage * stats.get(Stat::Might),
+            radius: self.radius * stats.get(Stat::Area),
+            remaining: self.lifetime,
+            pierce: self.pierce,
+            color: self.color,
+            crit_chance: CRIT_CHANCE * luck,
+            effects: self.effects
+                .iter()
+                .map(|&(effect, chance)| (orient(effect, direction), chance * luck))
+                .collect(),
+            hits: Vec::new(),
+        });
+    }
+}
+
+/// Points a knockback along `direction`, keeping its strength.
+fn orient(effect: StatusEffect, direction: Vec2) -> StatusEffect {
+    match effect {
+        StatusEffect::Knockback { impulse } => StatusEffect::Knockback { impulse: direction * impulse.length() },
+        other => other,
+    }
+}
+
+/// A projectile fired by the player, positions are world-space centers.
+pub struct Shot {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub damage: f32,
+    pub radius: f32,
+    pub remaining: f32,
+    /// Targets it can still go through.
+    pub pierce: usize,
+    pub color: Color,
+    /// Rolled separately for every target hit.
+    pub crit_chance: f32,
+    /// Effects with their chance to be applied on every hit.
+    pub effects: Vec<(StatusEffect, f32)>,
+    /// Targets already hit, so a piercing shot damages each one once.
+    hits: Vec<(TargetLayer, usize)>,
+}
+
+pub struct ShotSystem {
+    shots: Vec<Shot>,
+}
+
+impl ShotSystem {
+    pub fn new() -> Self {
+        Self { shots: Vec::new() }
+    }
+
+    pub fn spawn(&mut self, shot: Shot) {
+        self.shots.push(shot);
+    }
+
+    pub fn update(&mut self) {
+        let dt = get_frame_time();
+
+        for shot in self.shots.iter_mut() {
+            shot.position += shot.velocity;
+            shot.remaining -= dt;
+        }
+
+        self.shots.retain(|shot| shot.remaining > 0.0 && shot.pierce > 0);
+    }
+
+    /// Damages every target the shots overlap and returns the hits that landed.
+    pub fn resolve(&mut self, layer: TargetLayer, targets: &mut dyn Damageable) -> Vec<Hit> {
+        let mut hits = Vec::new();
+
+        for shot in self.shots.iter_mut() {
+            for index in targets.targets_in(shot.position, shot.radius) {
+                if shot.pierce == 0 {
+                    break;
+                }
+                if shot.hits.contains(&(layer, index)) {
+                    continue;
+                }
+
+                shot.hits.push((layer, index));
+                shot.pierce -= 1;
+
+                for &(effect, chance) in &shot.effects {
+                    if rand::gen_range(0.0, 1.0) < chance {
+                        targets.apply_effect(index, effect);
+                    }
+                }
+
+                let crit = rand::gen_range(0.0, 1.0) < shot.crit_chance;
+                let amount = if crit { shot.damage * CRIT_MULTIPLIER } else { shot.damage };
+                let dealt = targets.apply_damage(index, amount);
+                hits.push(Hit {
+                    layer,
+                    index,
+                    position: shot.position,
+                    amount: dealt,
+                    crit,
+                });
+            }
+        }
+
+        self.shots.retain(|shot| shot.pierce > 0);
+        hits
+    }
+
+    /// Forgets which targets of `layer` the shots already hit, for when its indices change.
+    pub fn forget_hits(&mut self, layer: TargetLayer) {
+        for shot in self.shots.iter_mut() {
+            shot.hits.retain(|&(hit_layer, _)| hit_layer != layer);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Shot> {
+        self.shots.iter()
+    }
+
+    pub fn draw(&self) {
+        for shot in &self.shots {
+            draw_circle(shot.position.x, shot.position.y, shot.radius, shot.color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Targets that every shot overlaps, wherever it is.
+    struct Dummies {
+        health: Vec<f32>,
+    }
+
+    impl Damageable for Dummies {
+        fn targets_in(&self, _center: Vec2, _radius: f32) -> Vec<usize> {
+            (0..self.health.len()).filter(|&i| self.health[i] > 0.0).collect()
+        }
+
+        fn apply_damage(&mut self, index: usize, amount: f32) -> f32 {
+            let damage = amount.min(self.health[index]);
+            self.health[index] -= damage;
+            damage
+        }
+    }
+
+    fn shot(pierce: usize) -> Shot {
+        Shot {
+            position: Vec2::ZERO,
+            velocity: Vec2::X,
+            damage: 10.0,
+            radius: 20.0,
+            remaining: 1.0,
+            pierce,
+            color: WHITE,
+            crit_chance: 0.0,
+            effects: Vec::new(),
+            hits: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pierce_limits_how_many_targets_a_shot_hits() {
+        let mut shots = ShotSystem::new();
+        shots.spawn(shot(2));
+        let mut dummies = Dummies { health: vec![100.0; 4] };
+
+        let hits = shots.resolve(TargetLayer::Enemies, &mut dummies);
+
+        assert_eq!(hits.iter().map(|hit| hit.index).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(dummies.health, vec![90.0, 90.0, 100.0, 100.0]);
+        // Spent shots are gone
+        assert_eq!(shots.iter().count(), 0);
+    }
+
+    #[test]
+    fn piercing_shots_hit_each_target_once() {
+        let mut shots = ShotSystem::new();
+        shots.spawn(shot(usize::MAX));
+        let mut dummies = Dummies { health: vec![100.0; 2] };
+
+        shots.resolve(TargetLayer::Enemies, &mut dummies);
+        let again = shots.resolve(TargetLayer::Enemies, &mut dummies);
+
+        assert!(again.is_empty());
+        assert_eq!(dummies.health, vec![90.0, 90.0]);
+    }
+
+    #[test]
+    fn forgotten_hits_can_land_again() {
+        let mut shots = ShotSystem::new();
+        shots.spawn(shot(usize::MAX));
+        let mut props = Dummies { health: vec![100.0] };
+        let mut enemies = Dummies { health: vec![100.0] };
+
+        shots.resolve(TargetLayer::Props, &mut props);
+        shots.resolve(TargetLayer::Enemies, &mut enemies);
+        shots.forget_hits(TargetLayer::Props);
+
+        // A different prop may sit at index 0 now, enemies are still remembered
+        assert_eq!(shots.resolve(TargetLayer::Props, &mut props).len(), 1);
+        assert!(shots.resolve(TargetLayer::Enemies, &mut enemies).is_empty());
+    }
+
+    #[test]
+    fn the_same_index_on_another_layer_is_a_new_target() {
+        let mut shots = ShotSystem::new();
+        shots.spawn(shot(usize::MAX));
+        let mut enemies = Dummies { health: vec![100.0] };
+        let mut props = Dummies { health: vec![5.0] };
+
+        shots.resolve(TargetLayer::Enemies, &mut enemies);
+        let hits = shots.resolve(TargetLayer::Props, &mut props);
+
+        // Only what the prop had left counts as dealt
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].amount, 5.0);
+    }
+}
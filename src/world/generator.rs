@@ -0,0 +1,296 @@
+use macroquad::prelude::*;
+use super::{Obstacle, ObstacleKind};
+use crate::destructibles::DestructibleKind;
+
+/// Tiles per side of a generated chunk.
+pub const CHUNK_TILES: usize = 16;
+pub const TILE_SIZE: f32 = 64.0;
+pub const CHUNK_SIZE: f32 = CHUNK_TILES as f32 * TILE_SIZE;
+
+// Tile ids in `images/tiles.png`
+const GRASS: u32 = 1;
+const GRASS_TUFTS: u32 = 2;
+const FLOWERS: u32 = 3;
+const WATER: u32 = 6;
+const SAND: u32 = 7;
+const DARK_GRASS: u32 = 8;
+
+/// Props are kept this far from the origin, where the player starts.
+const SPAWN_CLEARING: f32 = 320.0;
+
+/// A square piece of the infinite world, `coord` counts chunks from the origin.
+pub struct Chunk {
+    pub coord: IVec2,
+    /// Ground tile ids row by row from the bottom.
+    pub tiles: Vec<u32>,
+    pub obstacles: Vec<Obstacle>,
+    /// Breakable props, spawned when the chunk loads.
+    pub props: Vec<(DestructibleKind, Vec2)>,
+}
+
+impl Chunk {
+    pub fn origin(&self) -> Vec2 {
+        self.coord.as_vec2() * CHUNK_SIZE
+    }
+
+    pub fn rect(&self) -> Rect {
+        let origin = self.origin();
+        Rect::new(origin.x, origin.y, CHUNK_SIZE, CHUNK_SIZE)
+    }
+}
+
+/// Builds chunks from a seed, the same seed and coordinate always give the same chunk so
+/// chunks can be dropped and generated again later.
+pub struct WorldGenerator {
+    seed: u64,
+}
+
+impl WorldGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn chunk(&self, coord: IVec2) -> Chunk {
+        let first_tile = coord * CHUNK_TILES as i32;
+        let tiles: Vec<u32> = (0..CHUNK_TILES * CHUNK_TILES)
+            .map(|i| self.ground(first_tile + ivec2((i % CHUNK_TILES) as i32, (i / CHUNK_TILES) as i32)))
+            .collect();
+
+        let mut chunk = Chunk {
+            coord,
+            tiles,
+            obstacles: Vec::new(),
+            props: Vec::new(),
+        };
+        self.add_water_colliders(&mut chunk);
+        self.scatter_props(&mut chunk);
+        chunk
+    }
+
+    /// Ground tile for a tile coordinate, from two noise fields: one for ponds and one
+    /// for thick vegetation.
+    fn ground(&self, tile: IVec2) -> u32 {
+        let position = tile.as_vec2();
+        let height = self.noise(position / 14.0, 1);
+        let vegetation = self.noise(position / 9.0, 2);
+
+        // No ponds where the player starts
+        let near_spawn = position.length() < SPAWN_CLEARING / TILE_SIZE;
+
+        if height < 0.2 && !near_spawn {
+            WATER
+        } else if height < 0.26 && !near_spawn {
+            SAND
+        } else if vegetation > 0.7 {
+            DARK_GRASS
+        } else {
+            match hash(self.seed, tile.x, tile.y, 3) % 20 {
+                0 => FLOWERS,
+                1..=5 => GRASS_TUFTS,
+                _ => GRASS,
+            }
+        }
+    }
+
+    /// Each horizontal run of water tiles becomes one collider.
+    fn add_water_colliders(&self, chunk: &mut Chunk) {
+        let origin = chunk.origin();
+
+        for row in 0..CHUNK_TILES {
+            let mut column = 0;
+            while column < CHUNK_TILES {
+                if chunk.tiles[row * CHUNK_TILES + column] != WATER {
+                    column += 1;
+                    continue;
+                }
+
+                let start = column;
+                while column < CHUNK_TILES && chunk.tiles[row * CHUNK_TILES + column] == WATER {
+                    column += 1;
+                }
+
+                let position = origin + vec2(start as f32, row as f32) * TILE_SIZE;
+                let size = vec2((column - start) as f32 * TILE_SIZE, TILE_SIZE);
+                chunk.obstacles.push(Obstacle::new(ObstacleKind::Solid, position, size));
+            }
+        }
+    }
+
+    /// Trees gather on thick vegetation, rocks anywhere, and a few chunks get a ruined wall.
+    fn scatter_props(&self, chunk: &mut Chunk) {
+        let mut rng = ChunkRng::new(hash(self.seed, chunk.coord.x, chunk.coord.y, 4));
+        let origin = chunk.origin();
+
+        if rng.next_f32() < 0.15 {
+            let position = origin + vec2(rng.range(0.0, CHUNK_SIZE - 256.0), rng.range(0.0, CHUNK_SIZE - 64.0));
+            let size = if rng.next_f32() < 0.5 { vec2(256.0, 64.0) } else { vec2(64.0, 256.0) };
+            self.place(chunk, ObstacleKind::Wall, position, size);
+        }
+
+        let count = 4 + (rng.next_u64() % 8) as usize;
+        for _ in 0..count {
+            let position = origin + vec2(rng.range(0.0, CHUNK_SIZE), rng.range(0.0, CHUNK_SIZE));
+            let tile = (position / TILE_SIZE).floor().as_ivec2();
+
+            let kind = match self.ground(tile) {
+                DARK_GRASS => ObstacleKind::Tree,
+                _ if rng.next_f32() < 0.6 => ObstacleKind::Rock,
+                _ => ObstacleKind::Tree,
+            };
+            self.place(chunk, kind, position, kind.default_size());
+        }
+
+        let props = rng.next_u64() % 3;
+        for _ in 0..props {
+            let position = origin + vec2(rng.range(0.0, CHUNK_SIZE), rng.range(0.0, CHUNK_SIZE));
+            let kind = match rng.next_u64() % 5 {
+                0 => DestructibleKind::Brazier,
+                1 | 2 => DestructibleKind::Barrel,
+                _ => DestructibleKind::Crate,
+            };
+
+            if self.is_dry(Rect::new(position.x, position.y, 64.0, 64.0)) {
+                chunk.props.push((kind, position));
+            }
+        }
+    }
+
+    /// Adds a prop unless it would stand in water, on the shore or on the spawn.
+    fn place(&self, chunk: &mut Chunk, kind: ObstacleKind, position: Vec2, size: Vec2) {
+        let obstacle = Obstacle::new(kind, position, size);
+        let collider = obstacle.collider;
+
+        if collider.overlaps(&Rect::new(-SPAWN_CLEARING, -SPAWN_CLEARING, SPAWN_CLEARING * 2.0, SPAWN_CLEARING * 2.0)) {
+            return;
+        }
+
+        if self.is_dry(collider) {
+            chunk.obstacles.push(obstacle);
+        }
+    }
+
+    /// Whether an area stays clear of water and the shore around it.
+    fn is_dry(&self, area: Rect) -> bool {
+        let first = (area.point() / TILE_SIZE).floor().as_ivec2();
+        let last = ((area.point() + area.size()) / TILE_SIZE).floor().as_ivec2();
+
+        (first.y..=last.y).all(|y| (first.x..=last.x).all(|x| !matches!(self.ground(ivec2(x, y)), WATER | SAND)))
+    }
+
+    /// Smooth value noise in `0..1`, `salt` picks an independent field.
+    fn noise(&self, position: Vec2, salt: u64) -> f32 {
+        let cell = position.floor();
+        let local = position - cell;
+        let (x, y) = (cell.x as i32, cell.y as i32);
+
+        let corner = |dx: i32, dy: i32| (hash(self.seed, x + dx, y + dy, salt) >> 40) as f32 / (1u64 << 24) as f32;
+        let smooth = local * local * (3.0 - 2.0 * local);
+
+        let bottom = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * smooth.x;
+        let top = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * smooth.x;
+        bottom + (top - bottom) * smooth.y
+    }
+}
+
+/// Mixes the seed with a coordinate, so every spot gets its own stable random number.
+fn hash(seed: u64, x: i32, y: i32, salt: u64) -> u64 {
+    let mut value = seed
+        ^ (x as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ salt.wrapping_mul(0x1656_67B1_9E37_79F9);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+/// Small deterministic generator, the global `rand` would make chunks depend on the order
+/// they were generated in.
+struct ChunkRng(u64);
+
+impl ChunkRng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        hash(self.0, 0, 0, 0)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * self.next_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Everything a chunk is made of, in a form that can be compared.
+    type Contents = (Vec<u32>, Vec<(ObstacleKind, Rect)>, Vec<(DestructibleKind, Vec2)>);
+
+    fn contents(chunk: &Chunk) -> Contents {
+        let obstacles = chunk.obstacles.iter().map(|obstacle| (obstacle.kind, obstacle.collider)).collect();
+        (chunk.tiles.clone(), obstacles, chunk.props.clone())
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_chunks() {
+        let coords = [ivec2(0, 0), ivec2(3, -2), ivec2(-7, 5)];
+        let first = WorldGenerator::new(42);
+        let second = WorldGenerator::new(42);
+
+        // Generated in opposite orders, to catch any shared random state
+        let forward: Vec<_> = coords.iter().map(|&coord| contents(&first.chunk(coord))).collect();
+        let mut backward: Vec<_> = coords.iter().rev().map(|&coord| contents(&second.chunk(coord))).collect();
+        backward.reverse();
+
+        assert_eq!(forward, backward);
+        assert_eq!(contents(&first.chunk(coords[1])), forward[1]);
+    }
+
+    #[test]
+    fn different_seeds_give_different_worlds() {
+        let first = WorldGenerator::new(1);
+        let second = WorldGenerator::new(2);
+
+        let differs = (0..4).any(|x| first.chunk(ivec2(x, 1)).tiles != second.chunk(ivec2(x, 1)).tiles);
+        assert!(differs);
+    }
+
+    #[test]
+    fn props_keep_off_the_spawn() {
+        let clearing = Rect::new(-SPAWN_CLEARING, -SPAWN_CLEARING, SPAWN_CLEARING * 2.0, SPAWN_CLEARING * 2.0);
+
+        for seed in 0..20 {
+            let generator = WorldGenerator::new(seed);
+            for coord in [ivec2(0, 0), ivec2(-1, 0), ivec2(0, -1), ivec2(-1, -1)] {
+                let chunk = generator.chunk(coord);
+                let mut props = chunk.obstacles.iter().filter(|obstacle| obstacle.kind != ObstacleKind::Solid);
+                assert!(props.all(|obstacle| !obstacle.collider.overlaps(&clearing)));
+            }
+        }
+    }
+
+    #[test]
+    fn water_runs_become_colliders() {
+        let generator = WorldGenerator::new(7);
+
+        for x in -3..3 {
+            let chunk = generator.chunk(ivec2(x, 2));
+            let water = chunk.tiles.iter().filter(|&&tile| tile == WATER).count() as f32;
+            let covered: f32 = chunk
+                .obstacles
+                .iter()
+                .filter(|obstacle| obstacle.kind == ObstacleKind::Solid)
+                .map(|obstacle| obstacle.collider.w * obstacle.collider.h)
+                .sum();
+
+            assert_eq!(covered, water * TILE_SIZE * TILE_SIZE);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use macroquad::prelude::*;
+use nanoserde::DeJson;
+use crate::destructibles::{DestructibleKind, Destructibles};
+use crate::constants::{WORLD_WIDTH, WORLD_HEIGHT};
+use super::{NAV_CELL_SIZE, NavGrid, Obstacle, ObstacleKind, Obstacles, TileLayer, Tilemap, Tileset, World};
+
+/// A map exported from Tiled as JSON.
+const MAP_PATH: &str = "data/map.json";
+/// Tiles in this layer block movement instead of being drawn.
+const COLLISION_LAYER: &str = "collision";
+
+#[derive(DeJson)]
+struct MapData {
+    width: usize,
+    height: usize,
+    tilewidth: f32,
+    tileheight: f32,
+    #[nserde(default)]
+    layers: Vec<LayerData>,
+    #[nserde(default)]
+    tilesets: Vec<TilesetData>,
+}
+
+#[derive(DeJson)]
+struct LayerData {
+    #[nserde(rename = "type")]
+    kind: String,
+    name: String,
+    #[nserde(default = "true")]
+    visible: bool,
+    #[nserde(default = "1.0")]
+    opacity: f32,
+    /// Only in tile layers, exported as a plain array rather than base64.
+    #[nserde(default)]
+    data: Vec<u32>,
+    #[nserde(default)]
+    objects: Vec<ObjectData>,
+}
+
+#[derive(DeJson)]
+struct ObjectData {
+    /// Tiled 1.9 calls the type a class.
+    #[nserde(default, rename = "type")]
+    kind: String,
+    #[nserde(default)]
+    class: String,
+    x: f32,
+    y: f32,
+    #[nserde(default)]
+    width: f32,
+    #[nserde(default)]
+    height: f32,
+}
+
+#[derive(DeJson)]
+struct TilesetData {
+    firstgid: u32,
+    /// Empty for external tilesets, which aren't supported.
+    #[nserde(default)]
+    image: String,
+    #[nserde(default)]
+    columns: u32,
+    tilewidth: f32,
+    tileheight: f32,
+}
+
+/// Loads the world from the Tiled map at `data/map.json`.
+///
+/// Tile layers are drawn in order except the `collision` one, whose tiles become invisible
+/// obstacles. Objects are read by type: `PlayerSpawn`, `EnemySpawn`, an obstacle kind or a
+/// breakable prop kind.
+/// Without the map the world is an empty floor of the default size.
+pub async fn load_world() -> World {
+    let json = match load_string(MAP_PATH).await {
+        Ok(json) => json,
+        Err(_) => {
+            println!("Failed to load {}, using an empty world", MAP_PATH);
+            return empty_world();
+        }
+    };
+
+    let mut data = match MapData::deserialize_json(&json) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("Failed to parse {}: {}", MAP_PATH, err);
+            return empty_world();
+        }
+    };
+
+    let mut tilesets = Vec::new();
+    for tileset in std::mem::take(&mut data.tilesets) {
+        if tileset.image.is_empty() {
+            println!("Skipping an external tileset in {}, embed it in the map", MAP_PATH);
+            continue;
+        }
+
+        let path = resolve_path(MAP_PATH, &tileset.image);
+        let texture = match load_texture(&path).await {
+            Ok(texture) => {
+                // Linear filtering bleeds neighbouring tiles into the edges
+                texture.set_filter(FilterMode::Nearest);
+                Some(texture)
+            }
+            Err(_) => {
+                println!("Failed to load tileset {}, falling back to rectangles", path);
+                None
+            }
+        };
+
+        tilesets.push(Tileset {
+            first_gid: tileset.firstgid,
+            columns: tileset.columns,
+            tile_size: vec2(tileset.tilewidth, tileset.tileheight),
+            texture,
+        });
+    }
+
+    build_world(data, tilesets)
+}
+
+/// Lays out a parsed map, with its tilesets already loaded.
+fn build_world(data: MapData, tilesets: Vec<Tileset>) -> World {
+    let mut tilemap = Tilemap {
+        width: data.width,
+        height: data.height,
+        tile_size: vec2(data.tilewidth, data.tileheight),
+        layers: Vec::new(),
+        tilesets,
+    };
+
+    let size = tilemap.size();
+    let mut world = World {
+        bounds: Some(Rect::new(0.0, 0.0, size.x, size.y)),
+        nav: NavGrid::covering(Vec2::ZERO, size, NAV_CELL_SIZE),
+        obstacles: Obstacles::new(),
+        destructibles: Destructibles::new(),
+        tilemap: None,
+        player_spawn: size / 2.0,
+        enemy_spawns: Vec::new(),
+        seed: None,
+        streamer: None,
+    };
+
+    for layer in data.layers {
+        match layer.kind.as_str() {
+            "tilelayer" if layer.data.len() != data.width * data.height => {
+                println!("Skipping layer {} in {}, its tiles must be a plain array", layer.name, MAP_PATH);
+            }
+            "tilelayer" if layer.name == COLLISION_LAYER => {
+                add_collision_tiles(&mut world, &tilemap, &layer.data);
+            }
+            "tilelayer" if layer.visible => {
+                tilemap.layers.push(TileLayer {
+                    opacity: layer.opacity,
+                    tiles: layer.data,
+                });
+            }
+            "objectgroup" => {
+                for object in &layer.objects {
+                    add_object(&mut world, object, size.y);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    world.tilemap = Some(tilemap);
+    world
+}
+
+fn empty_world() -> World {
+    let size = vec2(WORLD_WIDTH, WORLD_HEIGHT);
+
+    World {
+        bounds: Some(Rect::new(0.0, 0.0, size.x, size.y)),
+        nav: NavGrid::covering(Vec2::ZERO, size, NAV_CELL_SIZE),
+        obstacles: Obstacles::new(),
+        destructibles: Destructibles::new(),
+        tilemap: None,
+        player_spawn: vec2(100.0, 100.0),
+        enemy_spawns: Vec::new(),
+        seed: None,
+        streamer: None,
+    }
+}
+
+/// Turns each horizontal run of collision tiles into one obstacle.
+fn add_collision_tiles(world: &mut World, tilemap: &Tilemap, tiles: &[u32]) {
+    for row in 0..tilemap.height {
+        let mut column = 0;
+        while column < tilemap.width {
+            if tiles[row * tilemap.width + column] == 0 {
+                column += 1;
+                continue;
+            }
+
+            let start = column;
+            while column < tilemap.width && tiles[row * tilemap.width + column] != 0 {
+                column += 1;
+            }
+
+            let first = tilemap.tile_rect(start, row);
+            let size = vec2(first.w * (column - start) as f32, first.h);
+            world.add_obstacle(Obstacle::new(ObstacleKind::Solid, first.point(), size));
+        }
+    }
+}
+
+/// Tiled objects hang down from their `y` while world positions stand on the bottom edge.
+fn add_object(world: &mut World, object: &ObjectData, map_height: f32) {
+    let kind = if object.class.is_empty() { &object.kind } else { &object.class };
+    let position = vec2(object.x, map_height - object.y - object.height);
+
+    match kind.as_str() {
+        "PlayerSpawn" => world.player_spawn = position,
+        "EnemySpawn" => world.enemy_spawns.push(position),
+        name => {
+            if let Some(kind) = DestructibleKind::from_name(name) {
+                world.destructibles.spawn(kind, position);
+            } else if let Some(kind) = ObstacleKind::from_name(name) {
+                let size = if object.width > 0.0 && object.height > 0.0 {
+                    vec2(object.width, object.height)
+                } else {
+                    kind.default_size()
+                };
+                world.add_obstacle(Obstacle::new(kind, position, size));
+            } else {
+                println!("Unknown object type {} in {}", name, MAP_PATH);
+            }
+        }
+    }
+}
+
+/// Resolves a path relative to the file that referenced it, Tiled uses `..` freely.
+fn resolve_path(from: &str, relative: &str) -> String {
+    let mut parts: Vec<&str> = from.split('/').collect();
+    parts.pop();
+
+    for part in relative.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            part => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::Damageable;
+
+    /// A 4x3 map of 32px tiles, Tiled rows count down from the top.
+    const MAP: &str = r#"{
+        "width": 4, "height": 3, "tilewidth": 32, "tileheight": 32, "infinite": false,
+        "layers": [
+            {"type": "tilelayer", "name": "ground", "data": [1,1,1,1, 1,2,2,1, 1,1,1,1]},
+            {"type": "tilelayer", "name": "decals", "visible": false, "data": [0,0,0,0, 0,0,0,0, 0,0,0,0]},
+            {"type": "tilelayer", "name": "collision", "data": [0,0,0,0, 0,0,0,0, 5,5,0,5]},
+            {"type": "tilelayer", "name": "broken", "data": [1,2,3]},
+            {"type": "objectgroup", "name": "objects", "objects": [
+                {"class": "PlayerSpawn", "x": 48, "y": 40},
+                {"type": "EnemySpawn", "x": 100, "y": 0},
+                {"type": "Crate", "x": 64, "y": 60},
+                {"type": "Wall", "x": 0, "y": 0, "width": 32, "height": 16},
+                {"type": "Unicorn", "x": 0, "y": 0}
+            ]}
+        ]
+    }"#;
+
+    fn world() -> World {
+        build_world(MapData::deserialize_json(MAP).unwrap(), Vec::new())
+    }
+
+    #[test]
+    fn only_visible_tile_layers_are_drawn() {
+        let world = world();
+        let tilemap = world.tilemap.as_ref().unwrap();
+
+        assert_eq!(tilemap.layers.len(), 1);
+        assert_eq!(tilemap.layers[0].opacity, 1.0);
+        assert_eq!(world.bounds, Some(Rect::new(0.0, 0.0, 128.0, 96.0)));
+    }
+
+    #[test]
+    fn collision_runs_block_the_bottom_row() {
+        let world = world();
+
+        // The last Tiled row is the bottom of the world, with a gap in its third tile
+        assert!(world.obstacles.blocks(Rect::new(40.0, 8.0, 4.0, 4.0)));
+        assert!(!world.obstacles.blocks(Rect::new(72.0, 8.0, 4.0, 4.0)));
+        assert!(world.obstacles.blocks(Rect::new(104.0, 8.0, 4.0, 4.0)));
+        assert!(!world.obstacles.blocks(Rect::new(40.0, 40.0, 4.0, 4.0)));
+    }
+
+    #[test]
+    fn objects_are_flipped_onto_the_world_y_axis() {
+        let world = world();
+
+        assert_eq!(world.player_spawn, vec2(48.0, 56.0));
+        assert_eq!(world.enemy_spawns, vec![vec2(100.0, 96.0)]);
+        // A 32x16 wall hanging from the top edge
+        assert!(world.obstacles.blocks(Rect::new(8.0, 84.0, 4.0, 4.0)));
+        assert!(!world.obstacles.blocks(Rect::new(8.0, 72.0, 4.0, 4.0)));
+        assert_eq!(world.destructibles.targets_in(vec2(70.0, 40.0), 4.0).len(), 1);
+    }
+
+    #[test]
+    fn paths_resolve_against_the_map() {
+        assert_eq!(resolve_path("data/map.json", "../images/tiles.png"), "images/tiles.png");
+        assert_eq!(resolve_path("data/map.json", "./tiles/grass.png"), "data/tiles/grass.png");
+    }
+}
@@ -0,0 +1,134 @@
+mod generator;
+mod map;
+mod nav_grid;
+mod obstacles;
+mod spatial_grid;
+mod streamer;
+mod tilemap;
+
+pub use map::load_world;
+pub use nav_grid::NavGrid;
+pub use obstacles::{Obstacle, ObstacleKind, Obstacles};
+pub use tilemap::{TileLayer, Tilemap, Tileset};
+
+use macroquad::prelude::*;
+use streamer::ChunkStreamer;
+use crate::destructibles::Destructibles;
+
+/// World units per navigation cell, about one enemy wide.
+const NAV_CELL_SIZE: f32 = 64.0;
+
+/// Level data the player and enemies move through, either a fixed map or an infinite
+/// world generated around the player.
+pub struct World {
+    /// Area everything is kept inside, `None` when the world is infinite.
+    pub bounds: Option<Rect>,
+    pub nav: NavGrid,
+    pub obstacles: Obstacles,
+    pub destructibles: Destructibles,
+    /// Ground tiles of a fixed map, `None` draws a plain floor.
+    pub tilemap: Option<Tilemap>,
+    pub player_spawn: Vec2,
+    /// Where enemies come back in, empty to use the whole area.
+    pub enemy_spawns: Vec<Vec2>,
+    /// What an infinite world was generated from, so a good one can be played again.
+    pub seed: Option<u64>,
+    streamer: Option<ChunkStreamer>,
+}
+
+impl World {
+    /// An endless world generated from `seed`, starting with the chunks around the origin.
+    pub async fn infinite(seed: u64) -> Self {
+        let mut world = Self {
+            bounds: None,
+            nav: NavGrid::new(Vec2::ZERO, NAV_CELL_SIZE, 0, 0),
+            obstacles: Obstacles::new(),
+            destructibles: Destructibles::new(),
+            tilemap: None,
+            player_spawn: Vec2::ZERO,
+            enemy_spawns: Vec::new(),
+            seed: Some(seed),
+            streamer: Some(ChunkStreamer::new(seed).await),
+        };
+
+        world.update(Vec2::ZERO);
+        world
+    }
+
+    /// Streams chunks around `focus` in the infinite world, rebuilding the obstacles and the
+    /// navigation grid whenever the loaded chunks change.
+    ///
+    /// Returns true when the destructibles were re-indexed.
+    pub fn update(&mut self, focus: Vec2) -> bool {
+        let Some(streamer) = self.streamer.as_mut() else {
+            return false;
+        };
+        let generated = streamer.update(focus);
+        if generated.is_empty() {
+            return false;
+        }
+
+        let area = streamer.loaded_area();
+        self.nav = NavGrid::covering(area.point(), area.size(), NAV_CELL_SIZE);
+        self.obstacles = Obstacles::new();
+
+        for obstacle in streamer.chunks().flat_map(|chunk| chunk.obstacles.iter()) {
+            self.nav.set_blocked_rect(obstacle.collider, true);
+            self.obstacles.add(*obstacle);
+        }
+
+        // Props come back intact when a chunk is generated again
+        self.destructibles.retain_in(area);
+        for chunk in generated.into_iter().filter_map(|coord| streamer.chunk(coord)) {
+            for &(kind, position) in &chunk.props {
+                self.destructibles.spawn(kind, position);
+            }
+        }
+
+        true
+    }
+
+    /// Area that currently exists, the whole map or the loaded chunks.
+    pub fn area(&self) -> Rect {
+        match (&self.streamer, self.bounds) {
+            (Some(streamer), _) => streamer.loaded_area(),
+            (None, Some(bounds)) => bounds,
+            (None, None) => self.nav.area(),
+        }
+    }
+
+    /// Adds an obstacle and blocks its cells for pathfinding.
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) {
+        self.nav.set_blocked_rect(obstacle.collider, true);
+        self.obstacles.add(obstacle);
+    }
+
+    /// Draws the ground under everything else, only around `view`.
+    pub fn draw_ground(&self, view: Rect) {
+        if let Some(streamer) = &self.streamer {
+            streamer.draw(view);
+        } else if let Some(tilemap) = &self.tilemap {
+            tilemap.draw(view);
+        } else {
+            let area = self.area();
+            draw_rectangle(area.x, area.y, area.w, area.h, Color::from_rgba(30, 30, 30, 255));
+        }
+    }
+
+    /// Keeps something of `size` at `position` inside the bounds, if there are any.
+    pub fn clamp(&self, position: Vec2, size: Vec2) -> Vec2 {
+        match self.bounds {
+            Some(bounds) => {
+                let max = (bounds.point() + bounds.size() - size).max(bounds.point());
+                position.clamp(bounds.point(), max)
+            }
+            None => position,
+        }
+    }
+}
+
+/// The band at the bottom of a sprite that collides with obstacles, so sprites can
+/// overlap props visually while their feet stay out.
+pub fn footprint(position: Vec2, size: Vec2) -> Rect {
+    Rect::new(position.x + size.x * 0.25, position.y, size.x * 0.5, size.y * 0.3)
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+
+/// Coarse grid over the world marking which cells can be walked through.
+///
+/// `origin` is the world position of the first cell's corner, so the grid can cover
+/// any window of the world rather than always starting at zero.
+pub struct NavGrid {
+    pub origin: Vec2,
+    pub cell_size: f32,
+    pub width: usize,
+    pub height: usize,
+    blocked: Vec<bool>,
+    /// Bumped every time a cell changes, so cached paths know when to rebuild.
+    revision: u32,
+}
+
+impl NavGrid {
+    pub fn new(origin: Vec2, cell_size: f32, width: usize, height: usize) -> Self {
+        Self {
+            origin,
+            cell_size,
+            width,
+            height,
+            blocked: vec![false; width * height],
+            revision: 0,
+        }
+    }
+
+    /// A grid that covers a `size` area starting at `origin`.
+    pub fn covering(origin: Vec2, size: Vec2, cell_size: f32) -> Self {
+        let width = (size.x / cell_size).ceil() as usize;
+        let height = (size.y / cell_size).ceil() as usize;
+        Self::new(origin, cell_size, width, height)
+    }
+
+    /// World-space rectangle the grid covers.
+    pub fn area(&self) -> Rect {
+        Rect::new(
+            self.origin.x,
+            self.origin.y,
+            self.width as f32 * self.cell_size,
+            self.height as f32 * self.cell_size,
+        )
+    }
+
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn is_blocked(&self, x: usize, y: usize) -> bool {
+        self.blocked[self.index(x, y)]
+    }
+
+    /// Blocks or clears every cell overlapping a world-space rectangle.
+    pub fn set_blocked_rect(&mut self, rect: Rect, blocked: bool) {
+        let start = ((rect.point() - self.origin) / self.cell_size).floor().max(Vec2::ZERO);
+        let end = ((rect.point() + rect.size() - self.origin) / self.cell_size).ceil();
+        let end_x = (end.x.max(0.0) as usize).min(self.width);
+        let end_y = (end.y.max(0.0) as usize).min(self.height);
+
+        for y in start.y as usize..end_y {
+            for x in start.x as usize..end_x {
+                let index = self.index(x, y);
+                self.blocked[index] = blocked;
+            }
+        }
+
+        self.revision += 1;
+    }
+}
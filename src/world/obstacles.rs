@@ -0,0 +1,157 @@
+use macroquad::prelude::*;
+use crate::enemies::PositionOverlap;
+use super::spatial_grid::SpatialGrid;
+use super::footprint;
+
+/// Size of the spatial grid cells, a few obstacles wide.
+const GRID_CELL_SIZE: f32 = 256.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ObstacleKind {
+    Rock,
+    Tree,
+    Wall,
+    /// Blocks without drawing anything, for colliders that come from map tiles.
+    Solid,
+}
+
+impl ObstacleKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Rock" => Some(Self::Rock),
+            "Tree" => Some(Self::Tree),
+            "Wall" => Some(Self::Wall),
+            "Solid" => Some(Self::Solid),
+            _ => None,
+        }
+    }
+
+    /// Drawn size when the map doesn't give one.
+    pub fn default_size(&self) -> Vec2 {
+        match self {
+            Self::Rock => vec2(88.0, 64.0),
+            Self::Tree => vec2(112.0, 176.0),
+            Self::Wall | Self::Solid => vec2(64.0, 64.0),
+        }
+    }
+
+    /// The part that blocks movement, trees only block at the trunk so things walk under
+    /// the canopy.
+    fn collider(&self, position: Vec2, size: Vec2) -> Rect {
+        match self {
+            Self::Rock => Rect::new(position.x, position.y, size.x, size.y * 0.7),
+            Self::Tree => Rect::new(position.x + size.x * 0.35, position.y, size.x * 0.3, size.y * 0.15),
+            Self::Wall | Self::Solid => Rect::new(position.x, position.y, size.x, size.y),
+        }
+    }
+}
+
+/// A static prop, `position` is the corner it stands on like the player and enemies.
+#[derive(Clone, Copy)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub collider: Rect,
+}
+
+impl Obstacle {
+    pub fn new(kind: ObstacleKind, position: Vec2, size: Vec2) -> Self {
+        Self {
+            kind,
+            position,
+            size,
+            collider: kind.collider(position, size),
+        }
+    }
+
+    fn draw(&self) {
+        if self.kind == ObstacleKind::Solid {
+            return;
+        }
+
+        let Vec2 { x, y } = self.position;
+        let Vec2 { x: w, y: h } = self.size;
+
+        // Shadow at the base so props read as standing on the ground
+        draw_ellipse(x + w / 2.0, y + 4.0, w * 0.5, 10.0, 0.0, Color::new(0.0, 0.0, 0.0, 0.3));
+
+        match self.kind {
+            ObstacleKind::Rock => {
+                draw_ellipse(x + w / 2.0, y + h * 0.45, w / 2.0, h * 0.45, 0.0, Color::from_rgba(105, 105, 115, 255));
+                draw_ellipse(x + w * 0.4, y + h * 0.6, w * 0.25, h * 0.2, 0.0, Color::from_rgba(140, 140, 150, 255));
+            }
+            ObstacleKind::Tree => {
+                draw_rectangle(x + w * 0.4, y, w * 0.2, h * 0.45, Color::from_rgba(100, 70, 40, 255));
+                draw_circle(x + w / 2.0, y + h - w / 2.0, w / 2.0, Color::from_rgba(40, 110, 50, 255));
+                draw_circle(x + w * 0.4, y + h - w * 0.4, w * 0.25, Color::from_rgba(60, 140, 70, 255));
+            }
+            ObstacleKind::Wall => {
+                draw_rectangle(x, y, w, h, Color::from_rgba(90, 80, 75, 255));
+                draw_rectangle(x, y + h - 12.0, w, 12.0, Color::from_rgba(125, 115, 105, 255));
+            }
+            ObstacleKind::Solid => {}
+        }
+    }
+}
+
+/// Every static obstacle in the world, bucketed for collision lookups.
+pub struct Obstacles {
+    items: Vec<Obstacle>,
+    grid: SpatialGrid,
+}
+
+impl Obstacles {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            grid: SpatialGrid::new(GRID_CELL_SIZE),
+        }
+    }
+
+    pub fn add(&mut self, obstacle: Obstacle) {
+        self.grid.insert(self.items.len(), obstacle.collider);
+        self.items.push(obstacle);
+    }
+
+    /// Whether `area` overlaps any collider.
+    pub fn blocks(&self, area: Rect) -> bool {
+        self.grid.query(area).any(|i| self.items[i].collider.overlaps(&area))
+    }
+
+    /// Where something of `size` ends up moving from `from` towards `to`.
+    ///
+    /// Each axis is tried on its own so movement slides along walls instead of sticking.
+    /// Anything that already overlaps a collider moves freely, so it can walk back out.
+    pub fn slide(&self, from: Vec2, to: Vec2, size: Vec2) -> Vec2 {
+        if self.blocks(footprint(from, size)) {
+            return to;
+        }
+
+        let mut result = from;
+        if !self.blocks(footprint(vec2(to.x, from.y), size)) {
+            result.x = to.x;
+        }
+        if !self.blocks(footprint(vec2(result.x, to.y), size)) {
+            result.y = to.y;
+        }
+
+        result
+    }
+
+    /// Draws the obstacles on one side of the player, farthest first.
+    pub fn draw(&self, target_pos: Vec2, overlap: PositionOverlap) {
+        let mut visible: Vec<&Obstacle> = self.items.iter()
+            .filter(|obstacle| match overlap {
+                PositionOverlap::Behind => obstacle.position.y > target_pos.y,
+                PositionOverlap::InFront => obstacle.position.y <= target_pos.y,
+            })
+            .collect();
+
+        visible.sort_by(|a, b| b.position.y.total_cmp(&a.position.y));
+
+        for obstacle in visible {
+            obstacle.draw();
+        }
+    }
+}
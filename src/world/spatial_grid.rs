@@ -0,0 +1,83 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// Buckets items by the world cells their bounds overlap, so lookups only touch nearby items.
+///
+/// Cells live in a map rather than a flat array, so the grid has no fixed extent.
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, item: usize, bounds: Rect) {
+        for cell in self.cells_overlapping(bounds) {
+            self.cells.entry(cell).or_default().push(item);
+        }
+    }
+
+    /// Items whose bounds share a cell with `area`, an item spanning several cells shows up
+    /// once per shared cell.
+    pub fn query(&self, area: Rect) -> impl Iterator<Item = usize> + '_ {
+        self.cells_overlapping(area)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+
+    fn cells_overlapping(&self, area: Rect) -> impl Iterator<Item = (i32, i32)> {
+        let start = (area.point() / self.cell_size).floor();
+        let end = ((area.point() + area.size()) / self.cell_size).floor();
+        let (start_x, start_y, end_x, end_y) = (start.x as i32, start.y as i32, end.x as i32, end.y as i32);
+
+        (start_y..=end_y).flat_map(move |y| (start_x..=end_x).map(move |x| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(grid: &SpatialGrid, area: Rect) -> Vec<usize> {
+        let mut items: Vec<usize> = grid.query(area).collect();
+        items.sort();
+        items.dedup();
+        items
+    }
+
+    #[test]
+    fn finds_only_nearby_items() {
+        let mut grid = SpatialGrid::new(32.0);
+        grid.insert(0, Rect::new(10.0, 10.0, 8.0, 8.0));
+        grid.insert(1, Rect::new(200.0, 200.0, 8.0, 8.0));
+
+        assert_eq!(found(&grid, Rect::new(0.0, 0.0, 20.0, 20.0)), vec![0]);
+        assert_eq!(found(&grid, Rect::new(190.0, 190.0, 20.0, 20.0)), vec![1]);
+        assert!(found(&grid, Rect::new(100.0, 100.0, 10.0, 10.0)).is_empty());
+    }
+
+    #[test]
+    fn items_spanning_cells_show_up_once_per_shared_cell() {
+        let mut grid = SpatialGrid::new(32.0);
+        grid.insert(0, Rect::new(20.0, 20.0, 30.0, 30.0));
+
+        assert_eq!(grid.query(Rect::new(0.0, 0.0, 64.0, 64.0)).count(), 4);
+        assert_eq!(found(&grid, Rect::new(40.0, 40.0, 1.0, 1.0)), vec![0]);
+    }
+
+    #[test]
+    fn negative_coordinates_have_their_own_cells() {
+        let mut grid = SpatialGrid::new(32.0);
+        grid.insert(0, Rect::new(-20.0, -20.0, 8.0, 8.0));
+
+        assert_eq!(found(&grid, Rect::new(-30.0, -30.0, 10.0, 10.0)), vec![0]);
+        assert!(found(&grid, Rect::new(4.0, 4.0, 10.0, 10.0)).is_empty());
+    }
+}
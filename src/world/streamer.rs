@@ -0,0 +1,103 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use super::generator::{Chunk, WorldGenerator, CHUNK_SIZE, CHUNK_TILES, TILE_SIZE};
+use super::tilemap::{Tileset, draw_tile};
+
+/// Chunks kept around the center chunk in every direction.
+const LOAD_RADIUS: i32 = 2;
+/// How far past the center chunk's edge the focus goes before chunks shift, as a fraction
+/// of a chunk, so walking along a chunk border doesn't reload chunks every frame.
+const RECENTER_MARGIN: f32 = 0.25;
+
+/// Keeps the chunks around a moving focus point generated and drops the ones left behind.
+pub struct ChunkStreamer {
+    generator: WorldGenerator,
+    tilesets: Vec<Tileset>,
+    chunks: HashMap<IVec2, Chunk>,
+    center: Option<IVec2>,
+}
+
+impl ChunkStreamer {
+    pub async fn new(seed: u64) -> Self {
+        let texture = match load_texture("images/tiles.png").await {
+            Ok(texture) => {
+                texture.set_filter(FilterMode::Nearest);
+                Some(texture)
+            }
+            Err(_) => {
+                println!("Failed to load the tiles texture, falling back to rectangles");
+                None
+            }
+        };
+
+        Self {
+            generator: WorldGenerator::new(seed),
+            tilesets: vec![Tileset {
+                first_gid: 1,
+                columns: 4,
+                tile_size: Vec2::splat(TILE_SIZE),
+                texture,
+            }],
+            chunks: HashMap::new(),
+            center: None,
+        }
+    }
+
+    /// Moves the loaded square of chunks along with `focus`, returns the chunks that were
+    /// just generated.
+    pub fn update(&mut self, focus: Vec2) -> Vec<IVec2> {
+        let chunk = (focus / CHUNK_SIZE).floor().as_ivec2();
+
+        if let Some(center) = self.center {
+            let center_point = (center.as_vec2() + 0.5) * CHUNK_SIZE;
+            let limit = CHUNK_SIZE * (0.5 + RECENTER_MARGIN);
+            if (focus - center_point).abs().max_element() < limit {
+                return Vec::new();
+            }
+        }
+
+        self.center = Some(chunk);
+        self.chunks.retain(|coord, _| (*coord - chunk).abs().max_element() <= LOAD_RADIUS);
+
+        let mut generated = Vec::new();
+        for y in -LOAD_RADIUS..=LOAD_RADIUS {
+            for x in -LOAD_RADIUS..=LOAD_RADIUS {
+                let coord = chunk + ivec2(x, y);
+                if !self.chunks.contains_key(&coord) {
+                    self.chunks.insert(coord, self.generator.chunk(coord));
+                    generated.push(coord);
+                }
+            }
+        }
+
+        generated
+    }
+
+    /// Area covered by the loaded chunks.
+    pub fn loaded_area(&self) -> Rect {
+        let center = self.center.unwrap_or(IVec2::ZERO);
+        let origin = (center - IVec2::splat(LOAD_RADIUS)).as_vec2() * CHUNK_SIZE;
+        let side = (LOAD_RADIUS * 2 + 1) as f32 * CHUNK_SIZE;
+        Rect::new(origin.x, origin.y, side, side)
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &Chunk> {
+        self.chunks.values()
+    }
+
+    pub fn chunk(&self, coord: IVec2) -> Option<&Chunk> {
+        self.chunks.get(&coord)
+    }
+
+    /// Draws the ground of the loaded chunks that overlap `view`.
+    pub fn draw(&self, view: Rect) {
+        for chunk in self.chunks.values().filter(|chunk| chunk.rect().overlaps(&view)) {
+            let origin = chunk.origin();
+
+            for (i, &gid) in chunk.tiles.iter().enumerate() {
+                let position = origin + vec2((i % CHUNK_TILES) as f32, (i / CHUNK_TILES) as f32) * TILE_SIZE;
+                draw_tile(&self.tilesets, gid, Rect::new(position.x, position.y, TILE_SIZE, TILE_SIZE), WHITE);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use macroquad::prelude::*;
+
+/// Tiles per side of a render chunk, only chunks overlapping the view get drawn.
+const CHUNK_TILES: usize = 16;
+/// Tiled keeps the flip flags in the top bits of every tile id.
+const TILE_ID_MASK: u32 = 0x1FFF_FFFF;
+
+/// One tile layer, `tiles` holds Tiled's global ids row by row from the top, 0 is empty.
+pub struct TileLayer {
+    pub opacity: f32,
+    pub tiles: Vec<u32>,
+}
+
+/// A tileset image cut into a grid, ids from `first_gid` map to its cells.
+pub struct Tileset {
+    pub first_gid: u32,
+    pub columns: u32,
+    pub tile_size: Vec2,
+    pub texture: Option<Texture2D>,
+}
+
+/// Visible tile layers of a map, `width` and `height` are in tiles.
+pub struct Tilemap {
+    pub width: usize,
+    pub height: usize,
+    pub tile_size: Vec2,
+    pub layers: Vec<TileLayer>,
+    pub tilesets: Vec<Tileset>,
+}
+
+impl Tilemap {
+    /// Size of the whole map in world units.
+    pub fn size(&self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32) * self.tile_size
+    }
+
+    /// World-space rectangle of the tile at `column`, `row`, Tiled rows count down from the
+    /// top while the world y axis points up.
+    pub fn tile_rect(&self, column: usize, row: usize) -> Rect {
+        Rect::new(
+            column as f32 * self.tile_size.x,
+            (self.height - 1 - row) as f32 * self.tile_size.y,
+            self.tile_size.x,
+            self.tile_size.y,
+        )
+    }
+
+    /// Draws every layer in order, chunk by chunk, skipping chunks outside `view`.
+    pub fn draw(&self, view: Rect) {
+        let chunk_size = self.tile_size * CHUNK_TILES as f32;
+        let chunks_x = self.width.div_ceil(CHUNK_TILES);
+        let chunks_y = self.height.div_ceil(CHUNK_TILES);
+
+        let first = (view.point() / chunk_size).floor().max(Vec2::ZERO);
+        let last = ((view.point() + view.size()) / chunk_size).floor();
+        let (first_x, first_y) = (first.x as usize, first.y as usize);
+        let last_x = (last.x.max(0.0) as usize).min(chunks_x.saturating_sub(1));
+        let last_y = (last.y.max(0.0) as usize).min(chunks_y.saturating_sub(1));
+
+        for layer in &self.layers {
+            let color = Color::new(1.0, 1.0, 1.0, layer.opacity);
+
+            // Chunks are counted from the bottom like the world, rows from the top like Tiled
+            for chunk_y in first_y..=last_y {
+                for chunk_x in first_x..=last_x {
+                    let columns = chunk_x * CHUNK_TILES..((chunk_x + 1) * CHUNK_TILES).min(self.width);
+                    let bottom = chunk_y * CHUNK_TILES;
+                    let top = ((chunk_y + 1) * CHUNK_TILES).min(self.height);
+
+                    for y in bottom..top {
+                        let row = self.height - 1 - y;
+                        for column in columns.clone() {
+                            let gid = layer.tiles[row * self.width + column] & TILE_ID_MASK;
+                            if gid != 0 {
+                                draw_tile(&self.tilesets, gid, self.tile_rect(column, row), color);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draws tile `gid` from whichever tileset holds it, filling `rect`.
+pub fn draw_tile(tilesets: &[Tileset], gid: u32, rect: Rect, color: Color) {
+    let tileset = tilesets.iter()
+        .filter(|tileset| tileset.first_gid <= gid)
+        .max_by_key(|tileset| tileset.first_gid);
+
+    match tileset.and_then(|tileset| tileset.texture.as_ref().map(|texture| (tileset, texture))) {
+        Some((tileset, texture)) => {
+            let local = gid - tileset.first_gid;
+            let source = Rect::new(
+                (local % tileset.columns.max(1)) as f32 * tileset.tile_size.x,
+                (local / tileset.columns.max(1)) as f32 * tileset.tile_size.y,
+                tileset.tile_size.x,
+                tileset.tile_size.y,
+            );
+
+            draw_texture_ex(texture, rect.x, rect.y, color, DrawTextureParams {
+                dest_size: Some(rect.size()),
+                source: Some(source),
+                flip_y: true,
+                ..Default::default()
+            });
+        }
+        None => {
+            // Without the image, tell tiles apart by shades of grey
+            let shade = 0.12 + (gid % 8) as f32 * 0.03;
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(shade, shade, shade, color.a));
+        }
+    }
+}